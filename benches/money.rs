@@ -0,0 +1,40 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rust_money::{Currency, Money};
+
+fn construction(c: &mut Criterion) {
+    c.bench_function("new", |b| b.iter(|| Money::new(black_box(1999), black_box("USD"))));
+    c.bench_function("new lower-case code", |b| b.iter(|| Money::new(black_box(1999), black_box("usd"))));
+}
+
+fn arithmetic(c: &mut Criterion) {
+    let a = Money::new(1999, "USD");
+    let b = Money::new(501, "USD");
+    c.bench_function("add", |bench| bench.iter(|| black_box(a).add(black_box(&b))));
+    c.bench_function("multiply", |bench| bench.iter(|| black_box(a).multiply(black_box(3))));
+    c.bench_function("absolute", |bench| bench.iter(|| black_box(a).absolute()));
+    c.bench_function("copy", |bench| bench.iter(|| black_box(black_box(a))));
+    // 改为引用注册表之前，每次运算都要复制一份 Currency
+    let currency = a.currency().clone();
+    c.bench_function("clone currency (previous per-operation cost)", |bench| {
+        bench.iter(|| black_box(&currency).clone())
+    });
+}
+
+fn allocation(c: &mut Criterion) {
+    let m = Money::new(100_000, "USD");
+    c.bench_function("split 10", |b| b.iter(|| black_box(m).split(black_box(10))));
+    c.bench_function("allocate 3", |b| b.iter(|| black_box(m).allocate(black_box(&[50, 30, 20]))));
+    let balances: Vec<Money> = (1..=100).map(|i| Money::new(i * 100, "USD")).collect();
+    c.bench_function("allocate_by_balances 100", |b| {
+        b.iter(|| m.allocate_by_balances(black_box(&balances), Default::default()))
+    });
+}
+
+fn lookup(c: &mut Criterion) {
+    c.bench_function("Currency::from_code", |b| b.iter(|| Currency::from_code(black_box("EUR"))));
+}
+
+criterion_group!(benches, construction, arithmetic, allocation, lookup);
+criterion_main!(benches);
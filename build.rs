@@ -0,0 +1,324 @@
+// 由 data/iso4217.tsv（现行货币）和 data/iso4217-historic.tsv（已停用货币）
+// 生成内置货币表的 iso_currencies! 调用，见 src/constants.rs；
+// 由 data/cldr-locales.tsv 和 data/cldr-symbols.tsv 生成区域表，见 src/locale.rs
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const ACTIVE: &str = "data/iso4217.tsv";
+const HISTORIC: &str = "data/iso4217-historic.tsv";
+const LOCALES: &str = "data/cldr-locales.tsv";
+const SYMBOLS: &str = "data/cldr-symbols.tsv";
+const COLUMNS: &[&str] = &[
+    "code", "numeric", "minor_units", "cash_rounding", "grapheme", "template", "decimal", "thousand", "grouping", "name",
+    "countries", "introduced", "withdrawn", "successor",
+];
+const LOCALE_COLUMNS: &[&str] = &["locale", "parent", "numbers", "decimal", "group", "minus", "pattern"];
+// src/formatter.rs 中 Digits 支持的 CLDR 数字系统
+const NUMBERING_SYSTEMS: &[&str] = &["latn", "arab", "arabext", "deva", "beng", "thai", "fullwide"];
+const SYMBOL_COLUMNS: &[&str] = &["locale", "currency", "symbol", "narrow"];
+
+struct Row {
+    file: &'static str,
+    line: usize,
+    columns: &'static [&'static str],
+    fields: Vec<String>,
+}
+
+impl Row {
+    fn field(&self, name: &str) -> &str {
+        &self.fields[self.columns.iter().position(|c| *c == name).unwrap()]
+    }
+
+    fn fail(&self, message: String) -> ! {
+        panic!("{}:{}: {}", self.file, self.line, message)
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", ACTIVE);
+    println!("cargo:rerun-if-changed={}", HISTORIC);
+    println!("cargo:rerun-if-changed={}", LOCALES);
+    println!("cargo:rerun-if-changed={}", SYMBOLS);
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let mut rows = read(ACTIVE, false);
+    rows.extend(read(HISTORIC, true));
+    rows.sort_by(|a, b| a.field("code").cmp(b.field("code")));
+    for pair in rows.windows(2) {
+        if pair[0].field("code") == pair[1].field("code") {
+            pair[1].fail(format!("`{}` is also defined in {}:{}", pair[1].field("code"), pair[0].file, pair[0].line));
+        }
+    }
+
+    let mut out = String::from("iso_currencies! {\n");
+    for row in &rows {
+        let successor = row.field("successor");
+        if !successor.is_empty() && !rows.iter().any(|r| r.field("code") == successor) {
+            row.fail(format!("unknown successor `{}`", successor));
+        }
+        // ISO 4217 对贵金属和特别提款权等没有辅币单位，记作 N.A.
+        let fraction = match row.field("minor_units") {
+            "N.A." => "0",
+            units => units,
+        };
+        writeln!(
+            out,
+            "    {} => {{ numeric_code: {:?}, fraction: {}, cash_rounding: {}, grapheme: {:?}, template: {:?}, decimal: {:?}, \
+             thousand: {:?}, grouping: {:?}, name: {:?}, countries: {:?}, introduced: {:?}, withdrawn: {:?}, successor: {:?} }},",
+            row.field("code"),
+            row.field("numeric"),
+            fraction,
+            row.field("cash_rounding"),
+            row.field("grapheme"),
+            row.field("template"),
+            row.field("decimal"),
+            row.field("thousand"),
+            row.field("grouping"),
+            row.field("name"),
+            row.field("countries"),
+            row.field("introduced"),
+            row.field("withdrawn"),
+            successor,
+        )
+        .unwrap();
+    }
+    // 数字代码到字母代码的索引：现行货币优先，其次是最近停用的货币
+    out.push_str("    ;\n    numeric: {\n");
+    let mut numerics: Vec<(u16, &Row)> = Vec::new();
+    for row in &rows {
+        let Ok(numeric) = row.field("numeric").parse::<u16>() else { continue };
+        let rank = |r: &Row| (r.field("withdrawn").is_empty(), r.field("withdrawn").to_string());
+        match numerics.iter_mut().find(|(n, _)| *n == numeric) {
+            Some(entry) if rank(row) > rank(entry.1) => entry.1 = row,
+            Some(_) => {}
+            None => numerics.push((numeric, row)),
+        }
+    }
+    numerics.sort_by_key(|(numeric, _)| *numeric);
+    for (numeric, row) in numerics {
+        writeln!(out, "        {} => {},", numeric, row.field("code")).unwrap();
+    }
+    out.push_str("    }\n}\n");
+    write("iso_currencies.rs", out);
+    write("locales.rs", locales(&rows));
+}
+
+fn write(name: &str, contents: String) {
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join(name);
+    fs::write(&path, contents).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+}
+
+// 读取一个数据文件，检查表头和字段数
+fn read_rows(file: &'static str, columns: &'static [&'static str]) -> Vec<Row> {
+    let data = fs::read_to_string(file).unwrap_or_else(|err| panic!("{}: {}", file, err));
+    let mut lines = data
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (_, header) = lines.next().unwrap_or_else(|| panic!("{}: missing header", file));
+    assert_eq!(header.split('\t').collect::<Vec<_>>(), columns, "{}: unexpected header", file);
+
+    lines
+        .map(|(line, text)| {
+            let row = Row { file, line, columns, fields: text.split('\t').map(str::to_string).collect() };
+            if row.fields.len() != columns.len() {
+                row.fail(format!("expected {} fields, found {}", columns.len(), row.fields.len()));
+            }
+            row
+        })
+        .collect()
+}
+
+// 读取并逐行校验一个货币数据文件
+fn read(file: &'static str, historic: bool) -> Vec<Row> {
+    let mut rows: Vec<Row> = Vec::new();
+    for row in read_rows(file, COLUMNS) {
+        let code = row.field("code");
+        if code.len() != 3 || !code.bytes().all(|b| b.is_ascii_uppercase()) {
+            row.fail(format!("invalid code `{}`", code));
+        }
+        if rows.last().is_some_and(|previous| previous.field("code") >= code) {
+            row.fail(format!("`{}` is out of alphabetical order", code));
+        }
+        let numeric = row.field("numeric");
+        if !numeric.is_empty() && (numeric.len() != 3 || !numeric.bytes().all(|b| b.is_ascii_digit())) {
+            row.fail(format!("invalid numeric code `{}`", numeric));
+        }
+        let minor_units = row.field("minor_units");
+        if minor_units != "N.A." && minor_units.parse::<u32>().is_err() {
+            row.fail(format!("invalid minor units `{}`", minor_units));
+        }
+        let cash_rounding = row.field("cash_rounding");
+        if cash_rounding.parse::<i64>().ok().filter(|c| *c > 0).is_none() {
+            row.fail(format!("invalid cash rounding `{}`", cash_rounding));
+        }
+        if !row.field("template").contains('1') {
+            row.fail(format!("invalid template `{}`", row.field("template")));
+        }
+        let grouping = row.field("grouping");
+        if !grouping.is_empty() && !is_grouping(grouping) {
+            row.fail(format!("invalid grouping `{}`, expected a pattern such as #,##,##0", grouping));
+        }
+        let countries = row.field("countries");
+        if !countries.is_empty() && !countries.split(' ').all(|c| c.len() == 2 && c.bytes().all(|b| b.is_ascii_uppercase())) {
+            row.fail(format!("invalid countries `{}`, expected space-separated alpha-2 codes", countries));
+        }
+        let (introduced, withdrawn) = (row.field("introduced"), row.field("withdrawn"));
+        for date in [introduced, withdrawn] {
+            if !date.is_empty() && !is_year_month(date) {
+                row.fail(format!("invalid date `{}`, expected YYYY-MM", date));
+            }
+        }
+        if !introduced.is_empty() && !withdrawn.is_empty() && introduced >= withdrawn {
+            row.fail(format!("`{}` is withdrawn before it is introduced", code));
+        }
+        if historic && withdrawn.is_empty() {
+            row.fail(format!("historic currency `{}` has no withdrawal date", code));
+        }
+        if !historic && (!withdrawn.is_empty() || !row.field("successor").is_empty()) {
+            row.fail(format!("active currency `{}` has a withdrawal date or successor; move it to {}", code, HISTORIC));
+        }
+        rows.push(row);
+    }
+    rows
+}
+
+// 校验区域表和符号表，生成 LOCALES 和 SYMBOLS 两个常量
+fn locales(currencies: &[Row]) -> String {
+    let mut locales = read_rows(LOCALES, LOCALE_COLUMNS);
+    for row in &mut locales {
+        for field in &mut row.fields {
+            *field = unescape(row.file, row.line, field);
+        }
+    }
+    for (i, row) in locales.iter().enumerate() {
+        let tag = row.field("locale");
+        if tag != "root" && !is_locale_tag(tag) {
+            row.fail(format!("invalid locale `{}`", tag));
+        }
+        let numbers = row.field("numbers");
+        if !NUMBERING_SYSTEMS.contains(&numbers) {
+            row.fail(format!("unsupported numbering system `{}`", numbers));
+        }
+        if locales[..i].iter().any(|r| r.field("locale") == tag && r.field("numbers") == numbers) {
+            row.fail(format!("duplicate locale `{}` with `{}` digits", tag, numbers));
+        }
+        let parent = row.field("parent");
+        if (tag == "root") != parent.is_empty() || (!parent.is_empty() && !locales.iter().any(|r| r.field("locale") == parent)) {
+            row.fail(format!("invalid parent `{}`", parent));
+        }
+        if locales[..i].iter().any(|r| r.field("locale") == tag && r.field("parent") != parent) {
+            row.fail(format!("parent `{}` differs from the first row of `{}`", parent, tag));
+        }
+        for name in ["decimal", "group"] {
+            if row.field(name).chars().count() != 1 {
+                row.fail(format!("{} must be a single character", name));
+            }
+        }
+        if row.field("minus").is_empty() {
+            row.fail("missing minus sign".to_string());
+        }
+        let pattern = row.field("pattern");
+        if pattern.split(';').count() > 2 || !pattern.split(';').all(|p| p.matches('¤').count() == 1 && p.contains('0')) {
+            row.fail(format!("invalid pattern `{}`", pattern));
+        }
+    }
+
+    let mut symbols = read_rows(SYMBOLS, SYMBOL_COLUMNS);
+    for row in &mut symbols {
+        for field in &mut row.fields {
+            *field = unescape(row.file, row.line, field);
+        }
+    }
+    for (i, row) in symbols.iter().enumerate() {
+        let (locale, currency) = (row.field("locale"), row.field("currency"));
+        if !locales.iter().any(|r| r.field("locale") == locale) {
+            row.fail(format!("unknown locale `{}`", locale));
+        }
+        if !currencies.iter().any(|r| r.field("code") == currency) {
+            row.fail(format!("unknown currency `{}`", currency));
+        }
+        if symbols[..i].iter().any(|r| r.field("locale") == locale && r.field("currency") == currency) {
+            row.fail(format!("duplicate symbol for `{}` in `{}`", currency, locale));
+        }
+        if row.field("symbol").is_empty() && row.field("narrow").is_empty() {
+            row.fail("missing symbol".to_string());
+        }
+    }
+
+    let mut out = String::from("const LOCALES: &[Locale] = &[\n");
+    for row in &locales {
+        writeln!(
+            out,
+            "    Locale {{ tag: {:?}, parent: {:?}, numbers: {:?}, decimal: {:?}, group: {:?}, minus: {:?}, pattern: {:?} }},",
+            row.field("locale"),
+            row.field("parent"),
+            row.field("numbers"),
+            row.field("decimal"),
+            row.field("group"),
+            row.field("minus"),
+            row.field("pattern"),
+        )
+        .unwrap();
+    }
+    out.push_str("];\n\nconst SYMBOLS: &[Symbol] = &[\n");
+    for row in &symbols {
+        writeln!(
+            out,
+            "    Symbol {{ locale: {:?}, currency: {:?}, symbol: {:?}, narrow: {:?} }},",
+            row.field("locale"),
+            row.field("currency"),
+            row.field("symbol"),
+            row.field("narrow"),
+        )
+        .unwrap();
+    }
+    out.push_str("];\n");
+    out
+}
+
+// 展开字段中的 \u{...} 转义
+fn unescape(file: &str, line: usize, field: &str) -> String {
+    let mut out = String::new();
+    let mut rest = field;
+    while let Some(start) = rest.find("\\u{") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find('}').map(|i| start + i).unwrap_or_else(|| panic!("{}:{}: unterminated escape", file, line));
+        let c = u32::from_str_radix(&rest[start + 3..end], 16)
+            .ok()
+            .and_then(char::from_u32)
+            .unwrap_or_else(|| panic!("{}:{}: invalid escape `{}`", file, line, &rest[start..=end]));
+        out.push(c);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// 语言（两到三个小写字母），可带地区（两个大写字母或三位数字）
+fn is_locale_tag(tag: &str) -> bool {
+    let (language, region) = tag.split_once('-').unwrap_or((tag, ""));
+    (2..=3).contains(&language.len())
+        && language.bytes().all(|b| b.is_ascii_lowercase())
+        && (region.is_empty()
+            || (region.len() == 2 && region.bytes().all(|b| b.is_ascii_uppercase()))
+            || (region.len() == 3 && region.bytes().all(|b| b.is_ascii_digit())))
+}
+
+// 形如 #,##0 或 #,##,##0：以 0 结尾，至少一个分组符，各组非空
+fn is_grouping(pattern: &str) -> bool {
+    pattern.ends_with('0')
+        && pattern.chars().all(|c| matches!(c, '#' | '0' | ','))
+        && pattern.split(',').count() > 1
+        && pattern.split(',').all(|group| !group.is_empty())
+}
+
+fn is_year_month(date: &str) -> bool {
+    let bytes = date.as_bytes();
+    bytes.len() == 7 && bytes[4] == b'-' && bytes.iter().enumerate().all(|(i, b)| i == 4 || b.is_ascii_digit())
+}
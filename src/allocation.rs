@@ -0,0 +1,450 @@
+use crate::{Money, MoneyError};
+
+/// Decides which parties receive the minor units left over when an amount
+/// cannot be divided exactly by the ratios.
+///
+/// Every strategy hands out at most one extra minor unit per party and only
+/// to parties with a non-zero ratio, so the parts always sum to the
+/// original amount.
+///
+/// ```
+/// use rust_money::{AllocationStrategy, Money};
+///
+/// let m = Money::new(100, "USD");
+/// let amounts = |s| -> Vec<i64> { m.split_with(3, s).unwrap().iter().map(Money::amount).collect() };
+/// assert_eq!(amounts(AllocationStrategy::ToFirst), [34, 33, 33]);
+/// assert_eq!(amounts(AllocationStrategy::ToLast), [33, 33, 34]);
+/// assert_eq!(amounts(AllocationStrategy::RoundRobin { offset: 1 }), [33, 34, 33]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AllocationStrategy {
+    /// Leftover units go to the first parties.
+    #[default]
+    ToFirst,
+    /// Leftover units go to the last parties.
+    ToLast,
+    /// Leftover units go to the parties whose exact shares lost the largest
+    /// fractions (Hamilton's method), ties broken by position.
+    LargestRemainder,
+    /// Leftover units go to consecutive parties starting at `offset`,
+    /// wrapping around; rotate the offset between runs to spread them fairly.
+    RoundRobin { offset: usize },
+    /// Leftover units go to parties picked by a deterministic shuffle, so the
+    /// same seed always yields the same allocation.
+    SeededRandom { seed: u64 },
+    /// Leftover units go to the parties with the largest ratios, ties broken
+    /// by position.
+    ToLargestRatio,
+}
+
+/// A party taking part in [`Money::allocate_capped`]: its ratio of the
+/// amount, plus an optional floor and cap.
+///
+/// ```
+/// use rust_money::{Money, Share};
+///
+/// let share = Share::new(2).with_min(Money::new(500, "USD")).with_max(Money::new(2000, "USD"));
+/// assert_eq!(share.ratio, 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+    pub ratio: u64,
+    pub min: Option<Money>,
+    pub max: Option<Money>,
+}
+
+impl Share {
+    pub fn new(ratio: u64) -> Self {
+        Share { ratio, min: None, max: None }
+    }
+
+    pub fn with_min(mut self, min: Money) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn with_max(mut self, max: Money) -> Self {
+        self.max = Some(max);
+        self
+    }
+}
+
+/// The outcome of a bounded allocation: one part per party, plus whatever
+/// could not be handed out because every party reached its cap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allocation {
+    pub parts: Vec<Money>,
+    pub remainder: Money,
+}
+
+/// Shares `amount` by ratio with every part kept between its floor and its
+/// cap: a party receives `max(min, pro-rata share)` and the shortfall is
+/// taken from the parties above their floors, while the excess over a cap
+/// goes to the parties below theirs. Parties without a ratio receive their
+/// floor. Returns the parts and the amount left once every party with a
+/// ratio is capped.
+pub(crate) fn allocate_capped_amounts(
+    amount: i64,
+    ratios: &[u64],
+    mins: &[i64],
+    maxs: &[Option<i64>],
+    strategy: AllocationStrategy,
+) -> Result<(Vec<i64>, i64), MoneyError> {
+    if ratios.is_empty() {
+        return Err(MoneyError::NoRatios);
+    }
+    if amount < 0 {
+        return Err(MoneyError::NegativeAmount);
+    }
+    for (&min, max) in mins.iter().zip(maxs) {
+        if min < 0 || max.is_some_and(|max| max < min) {
+            return Err(MoneyError::InvalidBounds);
+        }
+    }
+    let floors: i128 = mins.iter().map(|&m| m as i128).sum();
+    if floors > amount as i128 {
+        return Err(MoneyError::InsufficientAmount);
+    }
+
+    // 所有参与方都达到上限仍有剩余时，直接取上限，多出的部分作为余额
+    let capacity = (0..ratios.len()).try_fold(0i128, |sum, i| match (ratios[i], maxs[i]) {
+        (0, _) => Some(sum + mins[i] as i128),
+        (_, Some(max)) => Some(sum + max as i128),
+        (_, None) => None,
+    });
+    if let Some(capacity) = capacity.filter(|&c| c <= amount as i128) {
+        let parts = (0..ratios.len()).map(|i| if ratios[i] == 0 { mins[i] } else { maxs[i].unwrap() }).collect();
+        return Ok((parts, amount - capacity as i64));
+    }
+
+    // 每一方的份额为 clamp(λ * ratio, min, max)，求使总和等于 amount 的 λ。
+    // 每轮按比例分配未固定的参与方，若低于下限的缺口不小于超出上限的部分，
+    // 则 λ 只会变小，低于下限的参与方固定在下限；反之固定超出上限的参与方
+    let mut parts: Vec<Option<i64>> = (0..ratios.len()).map(|i| (ratios[i] == 0).then_some(mins[i])).collect();
+    loop {
+        let free: Vec<usize> = (0..ratios.len()).filter(|&i| parts[i].is_none()).collect();
+        let pool = amount - parts.iter().flatten().sum::<i64>();
+        if free.is_empty() {
+            return Ok((parts.into_iter().flatten().collect(), pool));
+        }
+        let total: i128 = free.iter().map(|&i| ratios[i] as i128).sum();
+        // 精确份额为 pool * ratio / total，比较时两边同乘 total
+        let scaled = |bound: i64| (bound as i128).checked_mul(total).ok_or(MoneyError::Overflow);
+        let (mut below, mut above) = (Vec::new(), Vec::new());
+        let (mut shortfall, mut excess) = (0i128, 0i128);
+        for &i in &free {
+            let share = pool as i128 * ratios[i] as i128;
+            let min = scaled(mins[i])?;
+            if share < min {
+                below.push(i);
+                shortfall = shortfall.checked_add(min - share).ok_or(MoneyError::Overflow)?;
+            } else if let Some(max) = maxs[i].map(scaled).transpose()?.filter(|&max| share > max) {
+                above.push(i);
+                excess = excess.checked_add(share - max).ok_or(MoneyError::Overflow)?;
+            }
+        }
+        if below.is_empty() && above.is_empty() {
+            // 精确份额在上下限之间，取整后的份额与之相差不到一个最小单位，仍在范围内
+            let weights: Vec<u128> = free.iter().map(|&i| ratios[i] as u128).collect();
+            for (&i, share) in free.iter().zip(allocate_amounts(pool, &weights, strategy)?) {
+                parts[i] = Some(share);
+            }
+            return Ok((parts.into_iter().flatten().collect(), 0));
+        }
+        if shortfall >= excess {
+            for i in below {
+                parts[i] = Some(mins[i]);
+            }
+        }
+        if excess >= shortfall {
+            for i in above {
+                parts[i] = maxs[i];
+            }
+        }
+    }
+}
+
+/// Fills `capacities` in order, returning the parts and the amount left
+/// once every tranche is full.
+pub(crate) fn allocate_waterfall_amounts(amount: i64, capacities: &[i64]) -> Result<(Vec<i64>, i64), MoneyError> {
+    if amount < 0 {
+        return Err(MoneyError::NegativeAmount);
+    }
+    if capacities.iter().any(|&c| c < 0) {
+        return Err(MoneyError::InvalidBounds);
+    }
+    let mut pool = amount;
+    let parts = capacities
+        .iter()
+        .map(|&capacity| {
+            let part = capacity.min(pool);
+            pool -= part;
+            part
+        })
+        .collect();
+    Ok((parts, pool))
+}
+
+/// Splits `amount` proportionally to `weights`, returning one amount per
+/// weight that sums exactly to `amount`.
+pub(crate) fn allocate_amounts(amount: i64, weights: &[u128], strategy: AllocationStrategy) -> Result<Vec<i64>, MoneyError> {
+    if weights.is_empty() {
+        return Err(MoneyError::NoRatios);
+    }
+    let total = weights
+        .iter()
+        .try_fold(0u128, |sum, &w| sum.checked_add(w))
+        .ok_or(MoneyError::Overflow)?;
+    if total == 0 {
+        return if amount == 0 { Ok(vec![0; weights.len()]) } else { Err(MoneyError::InvalidRatios) };
+    }
+    let total = i128::try_from(total).map_err(|_| MoneyError::Overflow)?;
+
+    let mut shares = Vec::with_capacity(weights.len());
+    // 每一份在整数除法中被截掉的部分，用于最大余数法
+    let mut fractions = Vec::with_capacity(weights.len());
+    let mut allocated: i128 = 0;
+    for &weight in weights {
+        let weight = i128::try_from(weight).map_err(|_| MoneyError::Overflow)?;
+        let exact = (amount as i128).checked_mul(weight).ok_or(MoneyError::Overflow)?;
+        let share = exact / total;
+        shares.push(share as i64);
+        fractions.push((exact % total).abs());
+        allocated += share;
+    }
+
+    // 截断损失的总和小于份数，因此每一方最多再分到一个最小单位
+    let remainder = amount as i128 - allocated;
+    let step = remainder.signum() as i64;
+    let eligible: Vec<usize> = (0..weights.len()).filter(|&i| weights[i] > 0).collect();
+    let order = strategy.order(eligible, weights, &fractions);
+    for &i in order.iter().take(remainder.unsigned_abs() as usize) {
+        shares[i] += step;
+    }
+    Ok(shares)
+}
+
+pub(crate) fn lcm(a: i128, b: i128) -> Result<i128, MoneyError> {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    (a / x).checked_mul(b).ok_or(MoneyError::Overflow)
+}
+
+impl AllocationStrategy {
+    // 返回接收剩余最小单位的优先顺序
+    fn order(self, mut eligible: Vec<usize>, weights: &[u128], fractions: &[i128]) -> Vec<usize> {
+        match self {
+            AllocationStrategy::ToFirst => {}
+            AllocationStrategy::ToLast => eligible.reverse(),
+            AllocationStrategy::LargestRemainder => eligible.sort_by(|&a, &b| fractions[b].cmp(&fractions[a])),
+            AllocationStrategy::RoundRobin { offset } => {
+                let start = eligible.iter().position(|&i| i >= offset % weights.len()).unwrap_or(0);
+                eligible.rotate_left(start);
+            }
+            AllocationStrategy::SeededRandom { seed } => {
+                let mut rng = SplitMix64(seed);
+                for i in (1..eligible.len()).rev() {
+                    let j = (rng.next() % (i as u64 + 1)) as usize;
+                    eligible.swap(i, j);
+                }
+            }
+            AllocationStrategy::ToLargestRatio => eligible.sort_by(|&a, &b| weights[b].cmp(&weights[a])),
+        }
+        eligible
+    }
+}
+
+// 简单的确定性伪随机数生成器，保证相同种子得到相同的分配结果
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRATEGIES: [AllocationStrategy; 8] = [
+        AllocationStrategy::ToFirst,
+        AllocationStrategy::ToLast,
+        AllocationStrategy::LargestRemainder,
+        AllocationStrategy::RoundRobin { offset: 0 },
+        AllocationStrategy::RoundRobin { offset: 5 },
+        AllocationStrategy::SeededRandom { seed: 0 },
+        AllocationStrategy::SeededRandom { seed: 42 },
+        AllocationStrategy::ToLargestRatio,
+    ];
+
+    #[test]
+    fn test_parts_sum_to_amount() {
+        let mut rng = SplitMix64(7);
+        for _ in 0..2000 {
+            let amount = match rng.next() % 4 {
+                0 => i64::MAX - (rng.next() % 1000) as i64,
+                1 => i64::MIN + (rng.next() % 1000) as i64,
+                _ => (rng.next() % 20001) as i64 - 10000,
+            };
+            let len = 1 + (rng.next() % 7) as usize;
+            let weights: Vec<u128> = (0..len).map(|_| (rng.next() % 5) as u128).collect();
+            for strategy in STRATEGIES {
+                let parts = match allocate_amounts(amount, &weights, strategy) {
+                    Ok(parts) => parts,
+                    Err(e) => {
+                        assert_eq!(e, MoneyError::InvalidRatios);
+                        assert!(weights.iter().all(|&w| w == 0));
+                        continue;
+                    }
+                };
+                assert_eq!(parts.iter().map(|&p| p as i128).sum::<i128>(), amount as i128, "{:?} {:?}", weights, strategy);
+                let total: u128 = weights.iter().sum();
+                for (&part, &w) in parts.iter().zip(&weights) {
+                    // 每一份与精确份额的差距不超过一个最小单位
+                    let exact = amount as i128 * w as i128;
+                    assert!((part as i128 * total as i128 - exact).abs() <= total as i128);
+                    if w == 0 {
+                        assert_eq!(part, 0);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_largest_remainder() {
+        // 精确份额为 14.29, 14.29, 71.43：第三方的截断损失最大
+        assert_eq!(allocate_amounts(100, &[1, 1, 5], AllocationStrategy::LargestRemainder), Ok(vec![14, 14, 72]));
+        assert_eq!(allocate_amounts(100, &[1, 1, 5], AllocationStrategy::ToFirst), Ok(vec![15, 14, 71]));
+        assert_eq!(allocate_amounts(-100, &[1, 1, 5], AllocationStrategy::LargestRemainder), Ok(vec![-14, -14, -72]));
+        assert_eq!(allocate_amounts(100, &[2, 3, 2], AllocationStrategy::LargestRemainder), Ok(vec![29, 43, 28]));
+    }
+
+    #[test]
+    fn test_to_largest_ratio() {
+        assert_eq!(allocate_amounts(10, &[1, 1, 1], AllocationStrategy::ToLargestRatio), Ok(vec![4, 3, 3]));
+        assert_eq!(allocate_amounts(11, &[1, 5, 5], AllocationStrategy::ToLargestRatio), Ok(vec![1, 5, 5]));
+        assert_eq!(allocate_amounts(12, &[1, 5, 5], AllocationStrategy::ToLargestRatio), Ok(vec![1, 6, 5]));
+    }
+
+    #[test]
+    fn test_round_robin_skips_zero_ratios() {
+        assert_eq!(allocate_amounts(5, &[1, 0, 1, 1], AllocationStrategy::RoundRobin { offset: 1 }), Ok(vec![1, 0, 2, 2]));
+        assert_eq!(allocate_amounts(5, &[1, 0, 1, 1], AllocationStrategy::RoundRobin { offset: 7 }), Ok(vec![2, 0, 1, 2]));
+    }
+
+    #[test]
+    fn test_seeded_random_is_deterministic() {
+        let weights = [1; 10];
+        let a = allocate_amounts(1005, &weights, AllocationStrategy::SeededRandom { seed: 9 }).unwrap();
+        let b = allocate_amounts(1005, &weights, AllocationStrategy::SeededRandom { seed: 9 }).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.iter().filter(|&&p| p == 101).count(), 5);
+    }
+
+    #[test]
+    fn test_capped_redistributes_excess() {
+        // 1000 按 1:1:2 分为 250, 250, 500；第三方上限 300，多出的 200 平分给前两方
+        let (parts, rest) = allocate_capped_amounts(1000, &[1, 1, 2], &[0, 0, 0], &[None, None, Some(300)], AllocationStrategy::ToFirst).unwrap();
+        assert_eq!((parts, rest), (vec![350, 350, 300], 0));
+        // 级联：第二方在重新分配后也达到上限
+        let (parts, rest) = allocate_capped_amounts(1000, &[1, 1, 2], &[0, 0, 0], &[None, Some(320), Some(300)], AllocationStrategy::ToFirst).unwrap();
+        assert_eq!((parts, rest), (vec![380, 320, 300], 0));
+    }
+
+    #[test]
+    fn test_capped_floors_and_remainder() {
+        let (parts, rest) = allocate_capped_amounts(1000, &[1, 1, 0], &[0, 100, 50], &[Some(200), Some(300), None], AllocationStrategy::ToFirst).unwrap();
+        assert_eq!((parts, rest), (vec![200, 300, 50], 450));
+        let (parts, rest) = allocate_capped_amounts(101, &[1, 1], &[0, 0], &[None, None], AllocationStrategy::ToLast).unwrap();
+        assert_eq!((parts, rest), (vec![50, 51], 0));
+    }
+
+    #[test]
+    fn test_capped_floors_are_lower_bounds() {
+        let capped = |amount, ratios: &[u64], mins: &[i64], maxs: &[Option<i64>]| {
+            allocate_capped_amounts(amount, ratios, mins, maxs, AllocationStrategy::ToFirst).unwrap()
+        };
+        // 份额已高于下限时，下限不影响结果
+        assert_eq!(capped(1000, &[1, 1], &[100, 0], &[None, None]), (vec![500, 500], 0));
+        assert_eq!(capped(1000, &[1, 3], &[0, 700], &[None, None]), (vec![250, 750], 0));
+        // 下限生效时，缺口由高于下限的参与方按比例承担
+        assert_eq!(capped(1000, &[1, 1], &[600, 0], &[None, None]), (vec![600, 400], 0));
+        assert_eq!(capped(1000, &[1, 3], &[300, 0], &[None, None]), (vec![300, 700], 0));
+        assert_eq!(capped(1000, &[2, 1, 1], &[0, 300, 0], &[None, None, None]), (vec![467, 300, 233], 0));
+        // 上下限同时生效
+        assert_eq!(capped(1000, &[1, 1, 2], &[0, 400, 0], &[None, None, Some(250)]), (vec![350, 400, 250], 0));
+        assert_eq!(capped(1000, &[1, 1, 1], &[0, 0, 450], &[Some(100), None, None]), (vec![100, 450, 450], 0));
+        assert_eq!(capped(100, &[1, 1], &[50, 50], &[None, None]), (vec![50, 50], 0));
+    }
+
+    #[test]
+    fn test_capped_random() {
+        let mut rng = SplitMix64(11);
+        for _ in 0..2000 {
+            let len = 1 + (rng.next() % 6) as usize;
+            let ratios: Vec<u64> = (0..len).map(|_| rng.next() % 4).collect();
+            let mins: Vec<i64> = (0..len).map(|_| if rng.next().is_multiple_of(2) { 0 } else { (rng.next() % 300) as i64 }).collect();
+            let maxs: Vec<Option<i64>> = mins.iter().map(|&min| rng.next().is_multiple_of(2).then(|| min + (rng.next() % 500) as i64)).collect();
+            let amount = (rng.next() % 2000) as i64;
+            let (parts, rest) = match allocate_capped_amounts(amount, &ratios, &mins, &maxs, AllocationStrategy::LargestRemainder) {
+                Ok(result) => result,
+                Err(e) => {
+                    assert_eq!(e, MoneyError::InsufficientAmount);
+                    assert!(mins.iter().sum::<i64>() > amount);
+                    continue;
+                }
+            };
+            assert_eq!(parts.iter().sum::<i64>() + rest, amount);
+            assert!(rest >= 0);
+            for i in 0..len {
+                assert!(parts[i] >= mins[i] && maxs[i].is_none_or(|max| parts[i] <= max), "{:?} {:?} {:?} {:?}", ratios, mins, maxs, parts);
+                if ratios[i] == 0 {
+                    assert_eq!(parts[i], mins[i]);
+                }
+                if rest > 0 && ratios[i] > 0 {
+                    assert_eq!(Some(parts[i]), maxs[i]);
+                }
+            }
+            // 高于下限的参与方按比例计不多于未达上限的参与方，取整误差除外
+            for i in (0..len).filter(|&i| ratios[i] > 0 && parts[i] > mins[i]) {
+                for j in (0..len).filter(|&j| ratios[j] > 0 && maxs[j].is_none_or(|max| parts[j] < max)) {
+                    let (ri, rj) = (ratios[i] as i64, ratios[j] as i64);
+                    assert!((parts[i] - 1) * rj <= (parts[j] + 1) * ri, "{:?} {:?} {:?} {:?}", ratios, mins, maxs, parts);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_capped_validation() {
+        let err = allocate_capped_amounts(100, &[1, 1], &[60, 60], &[None, None], AllocationStrategy::ToFirst);
+        assert_eq!(err, Err(MoneyError::InsufficientAmount));
+        let err = allocate_capped_amounts(100, &[1], &[60], &[Some(50)], AllocationStrategy::ToFirst);
+        assert_eq!(err, Err(MoneyError::InvalidBounds));
+        let err = allocate_capped_amounts(-100, &[1], &[0], &[None], AllocationStrategy::ToFirst);
+        assert_eq!(err, Err(MoneyError::NegativeAmount));
+    }
+
+    #[test]
+    fn test_waterfall() {
+        assert_eq!(allocate_waterfall_amounts(1000, &[300, 500, 400]), Ok((vec![300, 500, 200], 0)));
+        assert_eq!(allocate_waterfall_amounts(1000, &[300, 500]), Ok((vec![300, 500], 200)));
+        assert_eq!(allocate_waterfall_amounts(1000, &[]), Ok((vec![], 1000)));
+        assert_eq!(allocate_waterfall_amounts(1000, &[-1]), Err(MoneyError::InvalidBounds));
+    }
+
+    #[test]
+    fn test_zero_ratios() {
+        assert_eq!(allocate_amounts(0, &[0, 0], AllocationStrategy::ToFirst), Ok(vec![0, 0]));
+        assert_eq!(allocate_amounts(1, &[0, 0], AllocationStrategy::ToFirst), Err(MoneyError::InvalidRatios));
+        assert_eq!(allocate_amounts(1, &[], AllocationStrategy::ToFirst), Err(MoneyError::NoRatios));
+    }
+}
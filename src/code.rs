@@ -0,0 +1,308 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::constants::CurrencyCode;
+use crate::currency::{Currencies, Currency};
+use crate::MoneyError;
+
+// 由内置货币表生成代码常量、CurrencyCode 枚举和 Currencies::default 使用的货币列表，
+// 保证三者始终一致
+macro_rules! iso_currencies {
+    ($(
+        $code:ident => {
+            numeric_code: $numeric:literal,
+            fraction: $fraction:literal,
+            cash_rounding: $cash:literal,
+            grapheme: $grapheme:literal,
+            template: $template:literal,
+            decimal: $decimal:literal,
+            thousand: $thousand:literal,
+            grouping: $grouping:literal,
+            name: $name:literal,
+            countries: $countries:literal,
+            introduced: $introduced:literal,
+            withdrawn: $withdrawn:literal,
+            successor: $successor:literal $(,)?
+        }
+    ),* $(,)?
+    ;
+    numeric: { $($number:literal => $by_number:ident),* $(,)? }
+    ) => {
+        $(
+            pub const $code: &str = stringify!($code);
+        )*
+
+        /// The ISO 4217 alphabetic codes of the built-in currency table.
+        ///
+        /// Unlike a `&str`, a misspelt code is a compile error:
+        ///
+        /// ```
+        /// use rust_money::{CurrencyCode, Money};
+        ///
+        /// let m = Money::new(100, CurrencyCode::EUR);
+        /// assert_eq!(m.currency().code(), "EUR");
+        /// assert_eq!("eur".parse::<CurrencyCode>().unwrap(), CurrencyCode::EUR);
+        /// assert_eq!(CurrencyCode::from_numeric(978), Some(CurrencyCode::EUR));
+        /// ```
+        #[allow(clippy::upper_case_acronyms)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum CurrencyCode {
+            $($code,)*
+        }
+
+        impl CurrencyCode {
+            /// Every code of the built-in table, in alphabetical order.
+            pub const VARIANTS: &'static [CurrencyCode] = &[$(CurrencyCode::$code,)*];
+
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $(CurrencyCode::$code => stringify!($code),)*
+                }
+            }
+
+            /// The ISO 4217 numeric code as listed in the built-in table;
+            /// empty for currencies without one.
+            pub const fn numeric_code(self) -> &'static str {
+                match self {
+                    $(CurrencyCode::$code => $numeric,)*
+                }
+            }
+
+            /// Whether ISO 4217 lists this currency as withdrawn. Withdrawn
+            /// currencies stay in the table so historical amounts can still
+            /// be represented.
+            ///
+            /// ```
+            /// use rust_money::CurrencyCode;
+            ///
+            /// assert!(CurrencyCode::BYR.is_withdrawn());
+            /// assert!(!CurrencyCode::BYN.is_withdrawn());
+            /// ```
+            pub const fn is_withdrawn(self) -> bool {
+                match self {
+                    $(CurrencyCode::$code => !$withdrawn.is_empty(),)*
+                }
+            }
+
+            /// Looks up a code by its ISO 4217 numeric code. ISO may assign
+            /// the number of a withdrawn currency to a new one; the active
+            /// currency is then returned, and among withdrawn currencies
+            /// the one withdrawn last.
+            ///
+            /// ```
+            /// use rust_money::CurrencyCode;
+            ///
+            /// assert_eq!(CurrencyCode::from_numeric(933), Some(CurrencyCode::BYN));
+            /// // 974 belongs only to the withdrawn BYR
+            /// assert_eq!(CurrencyCode::from_numeric(974), Some(CurrencyCode::BYR));
+            /// ```
+            pub const fn from_numeric(numeric: u16) -> Option<CurrencyCode> {
+                match numeric {
+                    $($number => Some(CurrencyCode::$by_number),)*
+                    _ => None,
+                }
+            }
+
+            /// The built-in definition of this currency, regardless of any
+            /// changes made to the registry.
+            pub fn builtin(self) -> &'static $crate::Currency {
+                &Self::table()[self as usize]
+            }
+
+            // 内置定义的共享实例，Currencies::default 的各个表共用
+            pub(crate) fn shared(self) -> ::std::sync::Arc<$crate::Currency> {
+                ::std::sync::Arc::clone(&Self::table()[self as usize])
+            }
+
+            // 与 VARIANTS 同序，按判别值索引
+            fn table() -> &'static [::std::sync::Arc<$crate::Currency>] {
+                static TABLE: ::std::sync::OnceLock<Vec<::std::sync::Arc<$crate::Currency>>> = ::std::sync::OnceLock::new();
+                TABLE.get_or_init(|| {
+                    vec![$(
+                        ::std::sync::Arc::new(
+                            $crate::Currency::new_all(stringify!($code), $grapheme, $numeric, $template, $decimal, $thousand, $fraction)
+                                .with_cash_rounding($cash)
+                                .with_grouping($grouping.parse().unwrap_or_default())
+                                .with_builtin_history($name, $countries, $introduced, $withdrawn, $successor),
+                        ),
+                    )*]
+                })
+            }
+        }
+
+        // 每种内置货币对应一个零大小的类型标记，供 TypedMoney 使用
+        pub(crate) mod tags {
+            use super::CurrencyCode;
+
+            $(
+                #[doc = concat!("Type-level tag for `", stringify!($code), "`.")]
+                #[allow(clippy::upper_case_acronyms)]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+                pub struct $code;
+
+                impl $crate::typed::CurrencyTag for $code {
+                    const CODE: CurrencyCode = CurrencyCode::$code;
+                }
+            )*
+        }
+    };
+}
+
+pub(crate) use iso_currencies;
+
+impl CurrencyCode {
+    // 注册表中的定义，已被移除时退回内置定义
+    pub(crate) fn currency(self) -> Arc<Currency> {
+        Currencies::lookup(self.as_str()).unwrap_or_else(|| self.shared())
+    }
+
+    /// The ISO 4217 numeric code as a number.
+    pub fn numeric(self) -> Option<u16> {
+        self.numeric_code().parse().ok()
+    }
+}
+
+impl fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl AsRef<str> for CurrencyCode {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// Parses an alphabetic code in any case.
+impl FromStr for CurrencyCode {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // VARIANTS 按代码排序，可以二分查找
+        <[u8; 3]>::try_from(s.as_bytes())
+            .ok()
+            .map(|code| code.to_ascii_uppercase())
+            .and_then(|code| CurrencyCode::VARIANTS.binary_search_by(|c| c.as_str().as_bytes().cmp(&code[..])).ok())
+            .map(|i| CurrencyCode::VARIANTS[i])
+            .ok_or_else(|| MoneyError::UnknownCurrency(s.to_uppercase()))
+    }
+}
+
+impl TryFrom<&str> for CurrencyCode {
+    type Error = MoneyError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<CurrencyCode> for Currency {
+    /// Resolves the code through the registry, falling back to the built-in
+    /// definition if it has been unregistered.
+    fn from(code: CurrencyCode) -> Self {
+        Currency::clone(&code.currency())
+    }
+}
+
+impl Serialize for CurrencyCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = std::borrow::Cow::<str>::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::Grouping;
+
+    #[test]
+    fn test_variants_match_builtin_table() {
+        let currencies = Currencies::default();
+        assert_eq!(CurrencyCode::VARIANTS.len(), currencies.len());
+        for &code in CurrencyCode::VARIANTS {
+            let currency = currencies.get_currency_by_code(code.as_str()).unwrap();
+            assert_eq!(currency.numeric_code(), code.numeric_code());
+            assert_eq!(code.builtin().fraction(), currency.fraction());
+        }
+        assert!(CurrencyCode::VARIANTS.windows(2).all(|w| w[0] < w[1] && w[0].as_str() < w[1].as_str()));
+    }
+
+    // 独立于 build.rs 重新解析数据文件，核对生成的表
+    #[test]
+    fn test_builtin_table_matches_data() {
+        let data = [include_str!("../data/iso4217.tsv"), include_str!("../data/iso4217-historic.tsv")];
+        let mut rows: Vec<Vec<&str>> = data
+            .iter()
+            .flat_map(|data| data.lines().filter(|line| !line.is_empty() && !line.starts_with('#')).skip(1))
+            .map(|line| line.split('\t').collect())
+            .collect();
+        rows.sort();
+        assert_eq!(rows.len(), CurrencyCode::VARIANTS.len());
+        for (row, &code) in rows.iter().zip(CurrencyCode::VARIANTS) {
+            let currency = code.builtin();
+            let fraction = if row[2] == "N.A." { 0 } else { row[2].parse().unwrap() };
+            let date = |s: &str| if s.is_empty() { None } else { Some(format!("{}-01", s)) };
+            assert_eq!(row[0], code.as_str());
+            assert_eq!(row[1], currency.numeric_code(), "{}", code);
+            assert_eq!(fraction, currency.fraction(), "{}", code);
+            assert_eq!(row[3].parse::<i64>().unwrap(), currency.cash_rounding(), "{}", code);
+            assert_eq!(row[4..8], [currency.grapheme(), currency.template(), currency.decimal(), currency.thousand()], "{}", code);
+            assert_eq!(row[8].parse::<Grouping>().unwrap_or_default(), currency.grouping(), "{}", code);
+            assert_eq!(row[9], currency.name(), "{}", code);
+            assert_eq!(row[10], currency.countries().collect::<Vec<_>>().join(" "), "{}", code);
+            assert_eq!(date(row[11]), currency.introduced().map(|d| d.to_string()), "{}", code);
+            assert_eq!(date(row[12]), currency.withdrawn().map(|d| d.to_string()), "{}", code);
+            assert_eq!(row[13], currency.successor().unwrap_or(""), "{}", code);
+            assert_eq!(!row[12].is_empty(), code.is_withdrawn(), "{}", code);
+        }
+        assert_eq!(CurrencyCode::XDR.builtin().fraction(), 0);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!("ALL".parse::<CurrencyCode>(), Ok(CurrencyCode::ALL));
+        assert_eq!(CurrencyCode::try_from("jPy"), Ok(CurrencyCode::JPY));
+        assert_eq!("XYZ".parse::<CurrencyCode>(), Err(MoneyError::UnknownCurrency("XYZ".to_string())));
+        assert!("US".parse::<CurrencyCode>().is_err() && "USDX".parse::<CurrencyCode>().is_err() && "".parse::<CurrencyCode>().is_err());
+        for &code in CurrencyCode::VARIANTS {
+            assert_eq!(code.as_str().to_lowercase().parse(), Ok(code));
+        }
+        assert_eq!(CurrencyCode::GBP.to_string(), "GBP");
+    }
+
+    #[test]
+    fn test_numeric() {
+        assert_eq!(CurrencyCode::ALL.numeric(), Some(8));
+        assert_eq!(CurrencyCode::from_numeric(8), Some(CurrencyCode::ALL));
+        assert_eq!(CurrencyCode::from_numeric(999), None);
+        assert_eq!(CurrencyCode::from_numeric(974), Some(CurrencyCode::BYR));
+        // 每个数字代码都能找回，与现行货币共用时取现行货币
+        for &code in CurrencyCode::VARIANTS {
+            let Some(numeric) = code.numeric() else { continue };
+            let found = CurrencyCode::from_numeric(numeric).unwrap();
+            assert_eq!(found.numeric(), Some(numeric), "{}", code);
+            let shared_with_active = CurrencyCode::VARIANTS.iter().any(|c| !c.is_withdrawn() && c.numeric() == Some(numeric));
+            assert!(found == code || code.is_withdrawn(), "{} {}", code, found);
+            assert_eq!(found.is_withdrawn(), !shared_with_active, "{} {}", code, found);
+        }
+    }
+
+    #[test]
+    fn test_serde() {
+        assert_eq!(serde_json::to_string(&CurrencyCode::USD).unwrap(), r#""USD""#);
+        assert_eq!(serde_json::from_str::<CurrencyCode>(r#""usd""#).unwrap(), CurrencyCode::USD);
+        assert!(serde_json::from_str::<CurrencyCode>(r#""usdx""#).is_err());
+    }
+}
@@ -1,176 +1,5 @@
-pub const AED: &str = "AED";
-pub const AFN: &str = "AFN";
-pub const ALL: &str = "ALL";
-pub const AMD: &str = "AMD";
-pub const ANG: &str = "ANG";
-pub const AOA: &str = "AOA";
-pub const ARS: &str = "ARS";
-pub const AUD: &str = "AUD";
-pub const AWG: &str = "AWG";
-pub const AZN: &str = "AZN";
-pub const BAM: &str = "BAM";
-pub const BBD: &str = "BBD";
-pub const BDT: &str = "BDT";
-pub const BGN: &str = "BGN";
-pub const BHD: &str = "BHD";
-pub const BIF: &str = "BIF";
-pub const BMD: &str = "BMD";
-pub const BND: &str = "BND";
-pub const BOB: &str = "BOB";
-pub const BRL: &str = "BRL";
-pub const BSD: &str = "BSD";
-pub const BTN: &str = "BTN";
-pub const BWP: &str = "BWP";
-pub const BYN: &str = "BYN";
-pub const BYR: &str = "BYR";
-pub const BZD: &str = "BZD";
-pub const CAD: &str = "CAD";
-pub const CDF: &str = "CDF";
-pub const CHF: &str = "CHF";
-pub const CLF: &str = "CLF";
-pub const CLP: &str = "CLP";
-pub const CNY: &str = "CNY";
-pub const COP: &str = "COP";
-pub const CRC: &str = "CRC";
-pub const CUC: &str = "CUC";
-pub const CUP: &str = "CUP";
-pub const CVE: &str = "CVE";
-pub const CZK: &str = "CZK";
-pub const DJF: &str = "DJF";
-pub const DKK: &str = "DKK";
-pub const DOP: &str = "DOP";
-pub const DZD: &str = "DZD";
-pub const EEK: &str = "EEK";
-pub const EGP: &str = "EGP";
-pub const ERN: &str = "ERN";
-pub const ETB: &str = "ETB";
-pub const EUR: &str = "EUR";
-pub const FJD: &str = "FJD";
-pub const FKP: &str = "FKP";
-pub const GBP: &str = "GBP";
-pub const GEL: &str = "GEL";
-pub const GGP: &str = "GGP";
-pub const GHC: &str = "GHC";
-pub const GHS: &str = "GHS";
-pub const GIP: &str = "GIP";
-pub const GMD: &str = "GMD";
-pub const GNF: &str = "GNF";
-pub const GTQ: &str = "GTQ";
-pub const GYD: &str = "GYD";
-pub const HKD: &str = "HKD";
-pub const HNL: &str = "HNL";
-pub const HRK: &str = "HRK";
-pub const HTG: &str = "HTG";
-pub const HUF: &str = "HUF";
-pub const IDR: &str = "IDR";
-pub const ILS: &str = "ILS";
-pub const IMP: &str = "IMP";
-pub const INR: &str = "INR";
-pub const IQD: &str = "IQD";
-pub const IRR: &str = "IRR";
-pub const ISK: &str = "ISK";
-pub const JEP: &str = "JEP";
-pub const JMD: &str = "JMD";
-pub const JOD: &str = "JOD";
-pub const JPY: &str = "JPY";
-pub const KES: &str = "KES";
-pub const KGS: &str = "KGS";
-pub const KHR: &str = "KHR";
-pub const KMF: &str = "KMF";
-pub const KPW: &str = "KPW";
-pub const KRW: &str = "KRW";
-pub const KWD: &str = "KWD";
-pub const KYD: &str = "KYD";
-pub const KZT: &str = "KZT";
-pub const LAK: &str = "LAK";
-pub const LBP: &str = "LBP";
-pub const LKR: &str = "LKR";
-pub const LRD: &str = "LRD";
-pub const LSL: &str = "LSL";
-pub const LTL: &str = "LTL";
-pub const LVL: &str = "LVL";
-pub const LYD: &str = "LYD";
-pub const MAD: &str = "MAD";
-pub const MDL: &str = "MDL";
-pub const MGA: &str = "MGA";
-pub const MKD: &str = "MKD";
-pub const MMK: &str = "MMK";
-pub const MNT: &str = "MNT";
-pub const MOP: &str = "MOP";
-pub const MUR: &str = "MUR";
-pub const MRU: &str = "MRU";
-pub const MVR: &str = "MVR";
-pub const MWK: &str = "MWK";
-pub const MXN: &str = "MXN";
-pub const MYR: &str = "MYR";
-pub const MZN: &str = "MZN";
-pub const NAD: &str = "NAD";
-pub const NGN: &str = "NGN";
-pub const NIO: &str = "NIO";
-pub const NOK: &str = "NOK";
-pub const NPR: &str = "NPR";
-pub const NZD: &str = "NZD";
-pub const OMR: &str = "OMR";
-pub const PAB: &str = "PAB";
-pub const PEN: &str = "PEN";
-pub const PGK: &str = "PGK";
-pub const PHP: &str = "PHP";
-pub const PKR: &str = "PKR";
-pub const PLN: &str = "PLN";
-pub const PYG: &str = "PYG";
-pub const QAR: &str = "QAR";
-pub const RON: &str = "RON";
-pub const RSD: &str = "RSD";
-pub const RUB: &str = "RUB";
-pub const RUR: &str = "RUR";
-pub const RWF: &str = "RWF";
-pub const SAR: &str = "SAR";
-pub const SBD: &str = "SBD";
-pub const SCR: &str = "SCR";
-pub const SDG: &str = "SDG";
-pub const SEK: &str = "SEK";
-pub const SGD: &str = "SGD";
-pub const SHP: &str = "SHP";
-pub const SKK: &str = "SKK";
-pub const SLE: &str = "SLE";
-pub const SLL: &str = "SLL";
-pub const SOS: &str = "SOS";
-pub const SRD: &str = "SRD";
-pub const SSP: &str = "SSP";
-pub const STD: &str = "STD";
-pub const STN: &str = "STN";
-pub const SVC: &str = "SVC";
-pub const SYP: &str = "SYP";
-pub const SZL: &str = "SZL";
-pub const THB: &str = "THB";
-pub const TJS: &str = "TJS";
-pub const TMT: &str = "TMT";
-pub const TND: &str = "TND";
-pub const TOP: &str = "TOP";
-pub const TRL: &str = "TRL";
-pub const TRY: &str = "TRY";
-pub const TTD: &str = "TTD";
-pub const TWD: &str = "TWD";
-pub const TZS: &str = "TZS";
-pub const UAH: &str = "UAH";
-pub const UGX: &str = "UGX";
-pub const USD: &str = "USD";
-pub const UYU: &str = "UYU";
-pub const UZS: &str = "UZS";
-pub const VEF: &str = "VEF";
-pub const VES: &str = "VES";
-pub const VND: &str = "VND";
-pub const VUV: &str = "VUV";
-pub const WST: &str = "WST";
-pub const XAF: &str = "XAF";
-pub const XAG: &str = "XAG";
-pub const XAU: &str = "XAU";
-pub const XCD: &str = "XCD";
-pub const XDR: &str = "XDR";
-pub const XOF: &str = "XOF";
-pub const XPF: &str = "XPF";
-pub const YER: &str = "YER";
-pub const ZAR: &str = "ZAR";
-pub const ZMW: &str = "ZMW";
-pub const ZWD: &str = "ZWD";
-pub const ZWL: &str = "ZWL";
+// ISO 4217 内置货币表：生成代码常量、CurrencyCode 枚举以及 Currencies::default 的内容。
+// 表本身由 build.rs 从 data/iso4217.tsv 和 data/iso4217-historic.tsv 生成，修改货币请编辑这两个文件
+use crate::code::iso_currencies;
+
+include!(concat!(env!("OUT_DIR"), "/iso_currencies.rs"));
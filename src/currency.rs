@@ -1,321 +1,810 @@
-use std::collections::HashMap;
-use serde::{Deserialize, Serialize};
-use crate::formatter::Formatter;
-
-#[derive(Debug, Clone,Serialize, Deserialize)]
-pub struct Currency {
-    //货币的代码
-    pub(crate) code: String,
-    //货币的数字代码
-    numeric_code: &'static str,
-    //分数单位
-    pub fraction: usize,
-    //货币的符号
-    grapheme: &'static str,
-    //显示货币的模板
-    template: &'static str,
-    //表示小数点符号
-    decimal: &'static str,
-    //千位分隔符
-    thousand: &'static str,
-}
-
-impl PartialEq for Currency {
-    fn eq(&self, other: &Self) -> bool {
-        self.code == other.code
-    }
-}
-
-impl Currency {
-    pub fn new(code: &str) -> Self {
-        Self {
-            code: code.to_uppercase(),
-            // 其他字段的初始化可以在这里添加
-            numeric_code: "",
-            fraction: 0,
-            grapheme: "",
-            template: "",
-            decimal: "",
-            thousand: "",
-        }
-    }
-    pub fn new_all(code: &str, grapheme:&'static str,numeric_code:&'static str,template:&'static str, decimal:&'static str, thousand:&'static str, fraction:usize) -> Self {
-        Self {
-            code: code.to_uppercase(),
-            // 其他字段的初始化可以在这里添加
-            numeric_code,
-            fraction,
-            grapheme,
-            template,
-            decimal,
-            thousand,
-        }
-    }
-    pub fn get(&mut self) {
-        match Currencies::default().get_currency_by_code(self.code.as_str()) {
-            None => {
-            }
-            Some(v) => {
-                self.decimal=v.decimal;
-                self.fraction=v.fraction;
-                self.numeric_code=v.numeric_code;
-                self.grapheme=v.grapheme;
-                self.template=v.template;
-                self.thousand=v.thousand;
-            }
-        }
-    }
-    pub fn formatter(&self) -> Formatter {
-        Formatter::new(self.fraction,self.decimal,self.thousand,self.grapheme,self.template)
-    }
-}
-
-impl Eq for Currency {}
-
-pub struct Currencies {
-    currencies: HashMap<String, Currency>,
-}
-
-impl Currencies {
-    pub fn new() -> Self {
-        Self {
-            currencies: HashMap::new(),
-        }
-    }
-
-    pub fn currency_by_numeric_code(&self, code: &str) -> Option<&Currency> {
-        self.currencies.values().find(|c| c.numeric_code == code)
-    }
-
-    pub fn get_currency_by_code(&self, code: &str) -> Option<&Currency> {
-        self.currencies.get(code.to_uppercase().as_str())
-    }
-
-    pub fn add(&mut self, currency: Currency) {
-        self.currencies.insert(currency.code.clone(), currency);
-    }
-
-    fn add_bulk(&mut self, currencies: Vec<Currency>) {
-        for currency in currencies {
-            self.add(currency);
-        }
-    }
-
-}
-
-impl Default for Currencies {
-    fn default() -> Self {
-        let mut currencies = Currencies::new();
-        let currency_list = vec![
-            Currency { code: "AED".to_string(), numeric_code: "784", fraction: 2, grapheme: ".د.إ", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "AFN".to_string(), numeric_code: "971", fraction: 2, grapheme: "؋", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "ALL".to_string(), numeric_code: "008", fraction: 2, grapheme: "L", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "AMD".to_string(), numeric_code: "051", fraction: 2, grapheme: "դր.", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "ANG".to_string(), numeric_code: "532", fraction: 2, grapheme: "ƒ", template: "$1", decimal: ",", thousand: "." },
-            Currency { code: "AOA".to_string(), numeric_code: "973", fraction: 2, grapheme: "Kz", template: "1$", decimal: ".", thousand: "," },
-            Currency { code: "ARS".to_string(), numeric_code: "032", fraction: 2, grapheme: "$", template: "$1", decimal: ",", thousand: "." },
-            Currency { code: "AUD".to_string(), numeric_code: "036", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "AWG".to_string(), numeric_code: "533", fraction: 2, grapheme: "ƒ", template: "1$", decimal: ".", thousand: "," },
-            Currency { code: "AZN".to_string(), numeric_code: "944", fraction: 2, grapheme: "₼", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BAM".to_string(), numeric_code: "977", fraction: 2, grapheme: "KM", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BBD".to_string(), numeric_code: "052", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BDT".to_string(), numeric_code: "050", fraction: 2, grapheme: "৳", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BGN".to_string(), numeric_code: "975", fraction: 2, grapheme: "лв", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BHD".to_string(), numeric_code: "048", fraction: 3, grapheme: ".د.ب", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "BIF".to_string(), numeric_code: "108", fraction: 0, grapheme: "Fr", template: "1$", decimal: ".", thousand: "," },
-            Currency { code: "BMD".to_string(), numeric_code: "060", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BND".to_string(), numeric_code: "096", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BOB".to_string(), numeric_code: "068", fraction: 2, grapheme: "Bs.", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BRL".to_string(), numeric_code: "986", fraction: 2, grapheme: "R$", template: "$1", decimal: ",", thousand: "." },
-            Currency { code: "BSD".to_string(), numeric_code: "044", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BTN".to_string(), numeric_code: "064", fraction: 2, grapheme: "Nu.", template: "1$", decimal: ".", thousand: "," },
-            Currency { code: "BWP".to_string(), numeric_code: "072", fraction: 2, grapheme: "P", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "BYN".to_string(), numeric_code: "933", fraction: 2, grapheme: "р.", template: "1 $", decimal: ",", thousand: " " },
-            Currency { code: "BYR".to_string(), numeric_code: "", fraction: 0, grapheme: "р.", template: "1 $", decimal: ",", thousand: " " },
-            Currency { code: "BZD".to_string(), numeric_code: "084", fraction: 2, grapheme: "BZ$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "CAD".to_string(), numeric_code: "124", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "CDF".to_string(), numeric_code: "976", fraction: 2, grapheme: "FC", template: "1$", decimal: ".", thousand: "," },
-            Currency { code: "CHF".to_string(), numeric_code: "756", fraction: 2, grapheme: "CHF", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "CLF".to_string(), numeric_code: "990", fraction: 4, grapheme: "UF", template: "$1", decimal: ",", thousand: "." },
-            Currency { code: "CLP".to_string(), numeric_code: "152", fraction: 0, grapheme: "$", template: "$1", decimal: ",", thousand: "." },
-            Currency { code: "CNY".to_string(), numeric_code: "156", fraction: 2, grapheme: "元", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "COP".to_string(), numeric_code: "170", fraction: 2, grapheme: "$", template: "$1", decimal: ",", thousand: "." },
-            Currency { code: "CRC".to_string(), numeric_code: "188", fraction: 2, grapheme: "₡", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "CUC".to_string(), numeric_code: "931", fraction: 2, grapheme: "$", template: "1$", decimal: ".", thousand: "," },
-            Currency { code: "CUP".to_string(), numeric_code: "192", fraction: 2, grapheme: "$MN", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "CVE".to_string(), numeric_code: "132", fraction: 2, grapheme: "$", template: "1$", decimal: ".", thousand: "," },
-            Currency { code: "CZK".to_string(), numeric_code: "203", fraction: 2, grapheme: "Kč", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "DJF".to_string(), numeric_code: "262", fraction: 0, grapheme: "Fdj", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "DKK".to_string(), numeric_code: "208", fraction: 2, grapheme: "kr", template: "$ 1", decimal: ",", thousand: "." },
-            Currency { code: "DOP".to_string(), numeric_code: "214", fraction: 2, grapheme: "RD$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "DZD".to_string(), numeric_code: "012", fraction: 2, grapheme: ".د.ج", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "EEK".to_string(), numeric_code: "", fraction: 2, grapheme: "kr", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "EGP".to_string(), numeric_code: "818", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "ERN".to_string(), numeric_code: "232", fraction: 2, grapheme: "Nfk", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "ETB".to_string(), numeric_code: "230", fraction: 2, grapheme: "Br", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "EUR".to_string(), numeric_code: "978", fraction: 2, grapheme: "€", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "FJD".to_string(), numeric_code: "242", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "FKP".to_string(), numeric_code: "238", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "GBP".to_string(), numeric_code: "826", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "GEL".to_string(), numeric_code: "981", fraction: 2, grapheme: "ლ", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "GGP".to_string(), numeric_code: "", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "GHC".to_string(), numeric_code: "", fraction: 2, grapheme: "¢", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "GHS".to_string(), numeric_code: "936", fraction: 2, grapheme: "₵", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "GIP".to_string(), numeric_code: "292", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "GMD".to_string(), numeric_code: "270", fraction: 2, grapheme: "D", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "GNF".to_string(), numeric_code: "324", fraction: 0, grapheme: "FG", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "GTQ".to_string(), numeric_code: "320", fraction: 2, grapheme: "Q", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "GYD".to_string(), numeric_code: "328", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "HKD".to_string(), numeric_code: "344", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "HNL".to_string(), numeric_code: "340", fraction: 2, grapheme: "L", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "HRK".to_string(), numeric_code: "191", fraction: 2, grapheme: "kn", template: "1 $", decimal: ",", thousand: "." },
-            Currency { code: "HTG".to_string(), numeric_code: "332", fraction: 2, grapheme: "G", template: "1 $", decimal: ",", thousand: "." },
-            Currency { code: "HUF".to_string(), numeric_code: "348", fraction: 2, grapheme: "Ft", template: "1 $", decimal: ",", thousand: "." },
-            Currency { code: "IDR".to_string(), numeric_code: "360", fraction: 2, grapheme: "Rp", template: "$1", decimal: ",", thousand: "." },
-            Currency { code: "ILS".to_string(), numeric_code: "376", fraction: 2, grapheme: "₪", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "IMP".to_string(), numeric_code: "", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "INR".to_string(), numeric_code: "356", fraction: 2, grapheme: "₹", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "IQD".to_string(), numeric_code: "368", fraction: 3, grapheme: ".د.ع", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "IRR".to_string(), numeric_code: "364", fraction: 2, grapheme: "﷼", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "ISK".to_string(), numeric_code: "352", fraction: 0, grapheme: "kr", template: "$1", decimal: ",", thousand: "." },
-            Currency { code: "JEP".to_string(), numeric_code: "", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "JMD".to_string(), numeric_code: "388", fraction: 2, grapheme: "J$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "JOD".to_string(), numeric_code: "400", fraction: 3, grapheme: ".د.إ", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "JPY".to_string(), numeric_code: "392", fraction: 0, grapheme: "¥", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "KES".to_string(), numeric_code: "404", fraction: 2, grapheme: "KSh", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "KGS".to_string(), numeric_code: "417", fraction: 2, grapheme: "сом", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "KHR".to_string(), numeric_code: "116", fraction: 2, grapheme: "៛", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "KMF".to_string(), numeric_code: "174", fraction: 0, grapheme: "CF", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "KPW".to_string(), numeric_code: "408", fraction: 2, grapheme: "₩", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "KRW".to_string(), numeric_code: "410", fraction: 0, grapheme: "₩", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "KWD".to_string(), numeric_code: "414", fraction: 3, grapheme: ".د.ك", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "KYD".to_string(), numeric_code: "136", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "KZT".to_string(), numeric_code: "398", fraction: 2, grapheme: "₸", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "LAK".to_string(), numeric_code: "418", fraction: 2, grapheme: "₭", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "LBP".to_string(), numeric_code: "422", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "LKR".to_string(), numeric_code: "144", fraction: 2, grapheme: "₨", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "LRD".to_string(), numeric_code: "430", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "LSL".to_string(), numeric_code: "426", fraction: 2, grapheme: "L", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "LTL".to_string(), numeric_code: "", fraction: 2, grapheme: "Lt", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "LVL".to_string(), numeric_code: "", fraction: 2, grapheme: "Ls", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "LYD".to_string(), numeric_code: "434", fraction: 3, grapheme: ".د.ل", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "MAD".to_string(), numeric_code: "504", fraction: 2, grapheme: ".د.م", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "MDL".to_string(), numeric_code: "498", fraction: 2, grapheme: "lei", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "MGA".to_string(), numeric_code: "969", fraction: 2, grapheme: "Ar", template: "1$", decimal: ".", thousand: "," },
-            Currency { code: "MKD".to_string(), numeric_code: "807", fraction: 2, grapheme: "ден", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "MMK".to_string(), numeric_code: "104", fraction: 2, grapheme: "K", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "MNT".to_string(), numeric_code: "496", fraction: 2, grapheme: "₮", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "MOP".to_string(), numeric_code: "446", fraction: 2, grapheme: "P", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "MRU".to_string(), numeric_code: "929", fraction: 2, grapheme: "UM", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "MUR".to_string(), numeric_code: "480", fraction: 2, grapheme: "₨", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "MVR".to_string(), numeric_code: "462", fraction: 2, grapheme: "MVR", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "MWK".to_string(), numeric_code: "454", fraction: 2, grapheme: "MK", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "MXN".to_string(), numeric_code: "484", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "MYR".to_string(), numeric_code: "458", fraction: 2, grapheme: "RM", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "MZN".to_string(), numeric_code: "943", fraction: 2, grapheme: "MT", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "NAD".to_string(), numeric_code: "516", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "NGN".to_string(), numeric_code: "566", fraction: 2, grapheme: "₦", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "NIO".to_string(), numeric_code: "558", fraction: 2, grapheme: "C$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "NOK".to_string(), numeric_code: "578", fraction: 2, grapheme: "kr", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "NPR".to_string(), numeric_code: "524", fraction: 2, grapheme: "₨", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "NZD".to_string(), numeric_code: "554", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "OMR".to_string(), numeric_code: "512", fraction: 3, grapheme: "﷼", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "PAB".to_string(), numeric_code: "590", fraction: 2, grapheme: "B/.", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "PEN".to_string(), numeric_code: "604", fraction: 2, grapheme: "S/", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "PGK".to_string(), numeric_code: "598", fraction: 2, grapheme: "K", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "PHP".to_string(), numeric_code: "608", fraction: 2, grapheme: "₱", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "PKR".to_string(), numeric_code: "586", fraction: 2, grapheme: "₨", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "PLN".to_string(), numeric_code: "985", fraction: 2, grapheme: "zł", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "PYG".to_string(), numeric_code: "600", fraction: 0, grapheme: "Gs", template: "1$", decimal: ".", thousand: "," },
-            Currency { code: "QAR".to_string(), numeric_code: "634", fraction: 2, grapheme: "﷼", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "RON".to_string(), numeric_code: "946", fraction: 2, grapheme: "lei", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "RSD".to_string(), numeric_code: "941", fraction: 2, grapheme: "дин.", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "RUB".to_string(), numeric_code: "643", fraction: 2, grapheme: "₽", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "RUR".to_string(), numeric_code: "", fraction: 2, grapheme: "₽", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "RWF".to_string(), numeric_code: "646", fraction: 0, grapheme: "FRw", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "SAR".to_string(), numeric_code: "682", fraction: 2, grapheme: "﷼", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "SBD".to_string(), numeric_code: "090", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "SCR".to_string(), numeric_code: "690", fraction: 2, grapheme: "₨", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "SDG".to_string(), numeric_code: "938", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "SEK".to_string(), numeric_code: "752", fraction: 2, grapheme: "kr", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "SGD".to_string(), numeric_code: "702", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "SHP".to_string(), numeric_code: "654", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "SKK".to_string(), numeric_code: "", fraction: 2, grapheme: "Sk", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "SLE".to_string(), numeric_code: "925", fraction: 2, grapheme: "Le", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "SLL".to_string(), numeric_code: "694", fraction: 2, grapheme: "Le", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "SOS".to_string(), numeric_code: "706", fraction: 2, grapheme: "Sh", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "SRD".to_string(), numeric_code: "968", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "SSP".to_string(), numeric_code: "728", fraction: 2, grapheme: "£", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "STD".to_string(), numeric_code: "", fraction: 2, grapheme: "Db", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "STN".to_string(), numeric_code: "930", fraction: 2, grapheme: "Db", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "SVC".to_string(), numeric_code: "222", fraction: 2, grapheme: "₡", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "SYP".to_string(), numeric_code: "760", fraction: 2, grapheme: "£", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "SZL".to_string(), numeric_code: "748", fraction: 2, grapheme: "£", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "THB".to_string(), numeric_code: "764", fraction: 2, grapheme: "฿", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "TJS".to_string(), numeric_code: "972", fraction: 2, grapheme: "SM", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "TMT".to_string(), numeric_code: "934", fraction: 2, grapheme: "T", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "TND".to_string(), numeric_code: "788", fraction: 3, grapheme: ".د.ت", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "TOP".to_string(), numeric_code: "776", fraction: 2, grapheme: "T$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "TRL".to_string(), numeric_code: "", fraction: 2, grapheme: "₤", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "TRY".to_string(), numeric_code: "949", fraction: 2, grapheme: "₺", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "TTD".to_string(), numeric_code: "780", fraction: 2, grapheme: "TT$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "TWD".to_string(), numeric_code: "901", fraction: 2, grapheme: "NT$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "TZS".to_string(), numeric_code: "834", fraction: 0, grapheme: "TSh", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "UAH".to_string(), numeric_code: "980", fraction: 2, grapheme: "₴", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "UGX".to_string(), numeric_code: "800", fraction: 0, grapheme: "USh", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "USD".to_string(), numeric_code: "840", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "UYU".to_string(), numeric_code: "858", fraction: 2, grapheme: "$U", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "UZS".to_string(), numeric_code: "860", fraction: 2, grapheme: "so‘m", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "VEF".to_string(), numeric_code: "937", fraction: 2, grapheme: "Bs", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "VES".to_string(), numeric_code: "928", fraction: 2, grapheme: "Bs.S", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "VND".to_string(), numeric_code: "704", fraction: 0, grapheme: "₫", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "VUV".to_string(), numeric_code: "548", fraction: 0, grapheme: "Vt", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "WST".to_string(), numeric_code: "882", fraction: 2, grapheme: "T", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "XAF".to_string(), numeric_code: "950", fraction: 0, grapheme: "Fr", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "XAG".to_string(), numeric_code: "961", fraction: 0, grapheme: "oz t", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "XAU".to_string(), numeric_code: "959", fraction: 0, grapheme: "oz t", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "XCD".to_string(), numeric_code: "951", fraction: 2, grapheme: "$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "XDR".to_string(), numeric_code: "960", fraction: 0, grapheme: "SDR", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "XOF".to_string(), numeric_code: "952", fraction: 0, grapheme: "CFA", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "XPF".to_string(), numeric_code: "953", fraction: 0, grapheme: "₣", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "YER".to_string(), numeric_code: "886", fraction: 2, grapheme: "﷼", template: "1 $", decimal: ".", thousand: "," },
-            Currency { code: "ZAR".to_string(), numeric_code: "710", fraction: 2, grapheme: "R", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "ZMW".to_string(), numeric_code: "967", fraction: 2, grapheme: "ZK", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "ZWD".to_string(), numeric_code: "716", fraction: 2, grapheme: "Z$", template: "$1", decimal: ".", thousand: "," },
-            Currency { code: "ZWL".to_string(), numeric_code: "932", fraction: 2, grapheme: "Z$", template: "$1", decimal: ".", thousand: "," },
-        ];
-        currencies.add_bulk(currency_list);
-        currencies
-    }
-}
-
-
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_currency_get(){
-        let currencies = Currencies::default();
-        let option = currencies.get_currency_by_code("EUR");
-        println!("{:?}", option);
-    }
-    #[test]
-    fn test_currency_add(){
-        let mut currencies = Currencies::default();
-        let currency = Currency::new_all("ssdfd","sss","","","","",32);
-        currencies.add(currency);
-        let option = currencies.get_currency_by_code("ssdfd");
-        println!("{:?}", option);
-    }
-    #[test]
-    fn test_currency_equals(){
-        let currencies = Currencies::default();
-        let option = currencies.get_currency_by_code("EUR").unwrap();
-        let option2 = currencies.get_currency_by_code("USD").unwrap();
-        let option3 = currencies.get_currency_by_code("EUR").unwrap();
-        println!("{:?}", option.eq(option2));
-        println!("{:?}", option.eq(option3));
-    }
-}
\ No newline at end of file
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::constants::CurrencyCode;
+use crate::date::Date;
+use crate::formatter::{Formatter, Grouping};
+use crate::MoneyError;
+
+/// An ISO 4217 currency together with the information needed to format it.
+#[derive(Debug, Clone,Serialize, Deserialize)]
+pub struct Currency {
+    //货币的代码
+    pub(crate) code: String,
+    //货币的数字代码
+    numeric_code: Cow<'static, str>,
+    //分数单位
+    pub fraction: usize,
+    //现金支付时的最小舍入单位（以最小货币单位计）
+    cash_rounding: i64,
+    //货币的符号
+    grapheme: Cow<'static, str>,
+    //显示货币的模板
+    template: Cow<'static, str>,
+    //表示小数点符号
+    decimal: Cow<'static, str>,
+    //千位分隔符
+    thousand: Cow<'static, str>,
+    //数字分组方式
+    #[serde(default)]
+    grouping: Grouping,
+    //英文名称
+    name: Cow<'static, str>,
+    //使用该货币的国家和地区，ISO 3166 两位代码，以空格分隔
+    countries: Cow<'static, str>,
+    //启用日期
+    introduced: Option<Date>,
+    //停用日期
+    withdrawn: Option<Date>,
+    //接替它的货币代码
+    successor: Option<String>,
+}
+
+impl PartialEq for Currency {
+    fn eq(&self, other: &Self) -> bool {
+        // 同一注册表中的货币通常是同一个实例，先比较地址
+        std::ptr::eq(self, other) || self.code == other.code
+    }
+}
+
+impl Currency {
+    /// Creates a bare currency with the given code, zero fraction digits
+    /// and no symbol or template. The registry is not consulted, so this
+    /// never fails, even for unknown codes; use [`Currency::from_code`] for
+    /// a registered currency or [`Currency::new_all`] to define one.
+    ///
+    /// ```
+    /// # #![allow(deprecated)]
+    /// use rust_money::Currency;
+    ///
+    /// let usd = Currency::new("usd");
+    /// assert_eq!((usd.code(), usd.fraction()), ("USD", 0));
+    /// ```
+    #[deprecated(note = "does not consult the registry; use `Currency::from_code` or `Currency::new_all`")]
+    pub fn new(code: &str) -> Self {
+        Self {
+            code: code.to_uppercase(),
+            // 其他字段的初始化可以在这里添加
+            numeric_code: Cow::Borrowed(""),
+            fraction: 0,
+            cash_rounding: 1,
+            grapheme: Cow::Borrowed(""),
+            template: Cow::Borrowed(""),
+            decimal: Cow::Borrowed(""),
+            thousand: Cow::Borrowed(""),
+            grouping: Grouping::default(),
+            name: Cow::Borrowed(""),
+            countries: Cow::Borrowed(""),
+            introduced: None,
+            withdrawn: None,
+            successor: None,
+        }
+    }
+    /// Creates a currency from its full metadata. The strings may be
+    /// `&'static str` or owned `String`s, e.g. when read from a file.
+    pub fn new_all(
+        code: &str,
+        grapheme: impl Into<Cow<'static, str>>,
+        numeric_code: impl Into<Cow<'static, str>>,
+        template: impl Into<Cow<'static, str>>,
+        decimal: impl Into<Cow<'static, str>>,
+        thousand: impl Into<Cow<'static, str>>,
+        fraction: usize,
+    ) -> Self {
+        Self {
+            code: code.to_uppercase(),
+            // 其他字段的初始化可以在这里添加
+            numeric_code: numeric_code.into(),
+            fraction,
+            cash_rounding: 1,
+            grapheme: grapheme.into(),
+            template: template.into(),
+            decimal: decimal.into(),
+            thousand: thousand.into(),
+            grouping: Grouping::default(),
+            name: Cow::Borrowed(""),
+            countries: Cow::Borrowed(""),
+            introduced: None,
+            withdrawn: None,
+            successor: None,
+        }
+    }
+    /// Looks up a currency in the registry by its alphabetic code, in any case.
+    ///
+    /// ```
+    /// use rust_money::{Currency, MoneyError};
+    ///
+    /// assert_eq!(Currency::from_code("jpy").unwrap().fraction(), 0);
+    /// assert_eq!(Currency::from_code("XYZ"), Err(MoneyError::UnknownCurrency("XYZ".to_string())));
+    /// ```
+    pub fn from_code(code: &str) -> Result<Self, MoneyError> {
+        Currency::resolve(code).map(|currency| Currency::clone(&currency))
+    }
+
+    // 注册表中 Money 可以引用的货币，即代码为三个字母或数字的货币
+    pub(crate) fn resolve(code: &str) -> Result<Arc<Currency>, MoneyError> {
+        Some(code)
+            .filter(|code| Code::parse(code).is_some())
+            .and_then(Currencies::lookup)
+            .ok_or_else(|| MoneyError::UnknownCurrency(code.to_uppercase()))
+    }
+
+    // 与 resolve 相同，但注册表中没有的内置代码退回内置定义，供不会失败的构造函数使用
+    pub(crate) fn resolve_or_builtin(code: &str) -> Result<Arc<Currency>, MoneyError> {
+        Currency::resolve(code).or_else(|err| code.parse::<CurrencyCode>().map(CurrencyCode::shared).map_err(|_| err))
+    }
+
+    // Money 保存的紧凑代码，只用于 resolve 得到的货币
+    pub(crate) fn key(&self) -> Code {
+        Code {
+            code: Code::parse(&self.code).expect("registered currency codes have three characters"),
+            fraction: u8::try_from(self.fraction).expect("fraction fits in a byte"),
+        }
+    }
+
+    /// Fills in the metadata of this currency from the registry; see
+    /// [`Currencies::lookup`]. Unknown codes are left unchanged, so prefer
+    /// [`Currency::from_code`], which reports them.
+    pub fn get(&mut self) {
+        if let Some(v) = Currencies::lookup(self.code.as_str()) {
+            *self = Currency::clone(&v);
+        }
+    }
+
+    /// Sets the cash-rounding increment, in minor units.
+    ///
+    /// ```
+    /// use rust_money::Currency;
+    ///
+    /// let c = Currency::new_all("XTS", "¤", "963", "1 $", ".", ",", 2).with_cash_rounding(10);
+    /// assert_eq!(c.cash_rounding(), 10);
+    /// ```
+    pub fn with_cash_rounding(mut self, increment: i64) -> Self {
+        self.cash_rounding = increment;
+        self
+    }
+
+    /// Sets the digit grouping used by [`Currency::formatter`].
+    ///
+    /// ```
+    /// use rust_money::{Currency, Grouping};
+    ///
+    /// let c = Currency::new_all("XTS", "¤", "963", "$1", ".", ",", 2).with_grouping(Grouping::INDIAN);
+    /// assert_eq!(c.formatter().format(1_000_000_000), "¤1,00,00,000.00");
+    /// ```
+    pub fn with_grouping(mut self, grouping: Grouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Sets the English name, e.g. `"Deutsche Mark"`.
+    pub fn with_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the issuing countries as space-separated ISO 3166 alpha-2
+    /// codes, e.g. `"FR MC AD"`.
+    pub fn with_countries(mut self, countries: impl Into<Cow<'static, str>>) -> Self {
+        self.countries = countries.into();
+        self
+    }
+
+    pub fn with_introduced(mut self, date: Date) -> Self {
+        self.introduced = Some(date);
+        self
+    }
+
+    /// Marks the currency as withdrawn on `date`. Withdrawn currencies are
+    /// kept apart from active ones in [`Currencies`].
+    pub fn with_withdrawn(mut self, date: Date) -> Self {
+        self.withdrawn = Some(date);
+        self
+    }
+
+    pub fn with_successor(mut self, code: &str) -> Self {
+        self.successor = Some(code.to_uppercase());
+        self
+    }
+
+    // 内置表中的日期已由 build.rs 校验过
+    pub(crate) fn with_builtin_history(
+        self,
+        name: &'static str,
+        countries: &'static str,
+        introduced: &str,
+        withdrawn: &str,
+        successor: &str,
+    ) -> Self {
+        let mut currency = self.with_name(name).with_countries(countries);
+        currency.introduced = introduced.parse().ok();
+        currency.withdrawn = withdrawn.parse().ok();
+        currency.successor = Some(successor.to_string()).filter(|s| !s.is_empty());
+        currency
+    }
+
+    pub fn formatter(&self) -> Formatter {
+        Formatter::new(self.fraction,&self.decimal,&self.thousand,&self.grapheme,&self.template).with_grouping(self.grouping)
+    }
+
+    /// The upper-case ISO 4217 alphabetic code, e.g. `"USD"`.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// The ISO 4217 numeric code, e.g. `"840"`.
+    pub fn numeric_code(&self) -> &str {
+        &self.numeric_code
+    }
+
+    /// The ISO 4217 numeric code as a number, e.g. `840`.
+    pub fn numeric(&self) -> Option<u16> {
+        self.numeric_code.parse().ok()
+    }
+
+    /// The number of minor-unit digits, e.g. `2` for `USD`.
+    pub fn fraction(&self) -> usize {
+        self.fraction
+    }
+
+    /// The smallest amount, in minor units, that can be paid in cash, e.g.
+    /// `5` for `CHF` (0.05). `1` means cash payments are not rounded.
+    pub fn cash_rounding(&self) -> i64 {
+        self.cash_rounding
+    }
+
+    /// The currency symbol, e.g. `"$"`.
+    pub fn grapheme(&self) -> &str {
+        &self.grapheme
+    }
+
+    /// The display template, where `1` is replaced by the amount and `$` by the symbol.
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    pub fn decimal(&self) -> &str {
+        &self.decimal
+    }
+
+    pub fn thousand(&self) -> &str {
+        &self.thousand
+    }
+
+    /// How `thousand` groups the digits, e.g. [`Grouping::INDIAN`] for `INR`.
+    pub fn grouping(&self) -> Grouping {
+        self.grouping
+    }
+
+    /// The English name, e.g. `"Deutsche Mark"`; empty if unknown.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The ISO 3166 alpha-2 codes of the countries issuing the currency.
+    ///
+    /// ```
+    /// use rust_money::Currency;
+    ///
+    /// let chf = Currency::from_code("CHF").unwrap();
+    /// assert_eq!(chf.countries().collect::<Vec<_>>(), ["CH", "LI"]);
+    /// ```
+    pub fn countries(&self) -> impl Iterator<Item = &str> {
+        self.countries.split_whitespace()
+    }
+
+    /// The date the currency was introduced, if known.
+    pub fn introduced(&self) -> Option<Date> {
+        self.introduced
+    }
+
+    /// The date the currency was withdrawn; `None` for active currencies.
+    pub fn withdrawn(&self) -> Option<Date> {
+        self.withdrawn
+    }
+
+    /// The code of the currency that replaced this one, e.g. `"EUR"` for
+    /// `DEM`.
+    pub fn successor(&self) -> Option<&str> {
+        self.successor.as_deref()
+    }
+
+    pub fn is_withdrawn(&self) -> bool {
+        self.withdrawn.is_some()
+    }
+
+    /// Whether the currency was in use on `date`: introduced on or before
+    /// it, if the introduction is known, and withdrawn after it.
+    ///
+    /// ```
+    /// use rust_money::{Currency, Date};
+    ///
+    /// let dem = Currency::from_code("DEM").unwrap();
+    /// assert!(dem.is_active_on(Date::new(2001, 12, 31).unwrap()));
+    /// assert!(!dem.is_active_on(Date::new(2002, 3, 1).unwrap()));
+    /// ```
+    pub fn is_active_on(&self, date: Date) -> bool {
+        self.introduced.is_none_or(|introduced| introduced <= date) && self.withdrawn.is_none_or(|withdrawn| date < withdrawn)
+    }
+}
+
+impl Eq for Currency {}
+
+/// Same as [`Currency::from_code`].
+///
+/// ```
+/// use rust_money::Currency;
+///
+/// let eur: Currency = "EUR".parse().unwrap();
+/// assert_eq!(eur.grapheme(), "€");
+/// assert!("EURO".parse::<Currency>().is_err());
+/// ```
+impl FromStr for Currency {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Currency::from_code(s)
+    }
+}
+
+/// A lookup table of currencies keyed by their alphabetic code.
+///
+/// Entries are shared through [`Arc`]s: the built-in currencies are shared
+/// by every table, and a currency passed to [`Currencies::add`] is freed
+/// once it has been removed or replaced and the last `Arc` handed out for
+/// it is dropped.
+///
+/// Besides the alphabetic code, currencies can be found by numeric code, by
+/// symbol and by alias, all through hash indexes kept up to date by `add`
+/// and `remove`:
+///
+/// ```
+/// use rust_money::Currencies;
+///
+/// let currencies = Currencies::default();
+/// assert_eq!(currencies.currency_by_numeric(978).unwrap().code(), "EUR");
+/// assert!(currencies.currencies_by_grapheme("$").iter().any(|c| c.code() == "AUD"));
+/// assert_eq!(currencies.get_currency_by_alias("rmb").unwrap().code(), "CNY");
+/// ```
+///
+/// Withdrawn currencies are kept in a separate table: they can still be
+/// looked up by code, and by numeric code if no active currency uses it
+/// (the one withdrawn last if several do), but are left out of the symbol
+/// index and of [`Currencies::active_on`] for dates after their withdrawal.
+#[derive(Clone)]
+pub struct Currencies {
+    currencies: HashMap<String, Arc<Currency>>,
+    //已停用的货币
+    historical: HashMap<String, Arc<Currency>>,
+    //数字代码索引，共用数字代码的货币按加入顺序排列
+    by_numeric: HashMap<u16, Vec<Arc<Currency>>>,
+    //已停用货币的数字代码索引，按停用日期排列
+    historical_by_numeric: HashMap<u16, Vec<Arc<Currency>>>,
+    //符号索引，一个符号可能对应多种货币
+    by_grapheme: HashMap<String, Vec<Arc<Currency>>>,
+    //别名（大写）到货币代码
+    aliases: HashMap<String, String>,
+}
+
+// 内置的常用非 ISO 代码
+const BUILTIN_ALIASES: &[(&str, &str)] = &[("RMB", "CNY"), ("NIS", "ILS")];
+
+impl Currencies {
+    pub fn new() -> Self {
+        Self {
+            currencies: HashMap::new(),
+            historical: HashMap::new(),
+            by_numeric: HashMap::new(),
+            historical_by_numeric: HashMap::new(),
+            by_grapheme: HashMap::new(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// The number of currencies, active and withdrawn.
+    pub fn len(&self) -> usize {
+        self.currencies.len() + self.historical.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.currencies.is_empty() && self.historical.is_empty()
+    }
+
+    /// Same as [`Currencies::currency_by_numeric`], with the numeric code as
+    /// a string such as `"008"`.
+    pub fn currency_by_numeric_code(&self, code: &str) -> Option<&Arc<Currency>> {
+        self.currency_by_numeric(code.parse().ok()?)
+    }
+
+    /// Looks up a currency by its ISO 4217 numeric code. If several
+    /// currencies share a numeric code, the one added last is returned;
+    /// withdrawn currencies are only returned if no active currency uses
+    /// the code, the one withdrawn last first.
+    pub fn currency_by_numeric(&self, numeric: u16) -> Option<&Arc<Currency>> {
+        self.by_numeric
+            .get(&numeric)
+            .or_else(|| self.historical_by_numeric.get(&numeric))
+            .and_then(|candidates| candidates.last())
+    }
+
+    /// All currencies using `grapheme` as their symbol, in the order they
+    /// were added.
+    pub fn currencies_by_grapheme(&self, grapheme: &str) -> &[Arc<Currency>] {
+        self.by_grapheme.get(grapheme).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn get_currency_by_code(&self, code: &str) -> Option<&Arc<Currency>> {
+        let code = upper_case(code);
+        self.currencies.get(code.as_ref()).or_else(|| self.historical.get(code.as_ref()))
+    }
+
+    /// The currencies that have not been withdrawn, in no particular order.
+    pub fn active(&self) -> impl Iterator<Item = &Arc<Currency>> + '_ {
+        self.currencies.values()
+    }
+
+    /// The withdrawn currencies, in no particular order.
+    pub fn historical(&self) -> impl Iterator<Item = &Arc<Currency>> + '_ {
+        self.historical.values()
+    }
+
+    /// The currencies, active or withdrawn, that were in use on `date`,
+    /// sorted by code. Currencies without a known introduction date count
+    /// as in use since before any date.
+    ///
+    /// ```
+    /// use rust_money::{Currencies, Date};
+    ///
+    /// let currencies = Currencies::default();
+    /// let codes = |date: &str| -> Vec<&str> {
+    ///     currencies.active_on(date.parse::<Date>().unwrap()).iter().map(|c| c.code()).collect()
+    /// };
+    /// assert!(codes("1998-06-01").contains(&"DEM") && !codes("1998-06-01").contains(&"EUR"));
+    /// assert!(codes("2002-03-01").contains(&"EUR") && !codes("2002-03-01").contains(&"DEM"));
+    /// ```
+    pub fn active_on(&self, date: Date) -> Vec<&Arc<Currency>> {
+        let mut active: Vec<_> = self
+            .currencies
+            .values()
+            .chain(self.historical.values())
+            .filter(|c| c.is_active_on(date))
+            .collect();
+        active.sort_by(|a, b| a.code.cmp(&b.code));
+        active
+    }
+
+    /// The currency that directly replaced `code`, if it is known and in
+    /// the table.
+    ///
+    /// ```
+    /// use rust_money::Currencies;
+    ///
+    /// let currencies = Currencies::default();
+    /// assert_eq!(currencies.successor("VEF").unwrap().code(), "VES");
+    /// assert_eq!(currencies.successor("ZWD").unwrap().code(), "ZWL");
+    /// assert_eq!(currencies.latest_successor("ZWD").unwrap().code(), "ZWG");
+    /// assert!(currencies.successor("USD").is_none());
+    /// ```
+    pub fn successor(&self, code: &str) -> Option<&Arc<Currency>> {
+        self.get_currency_by_code(self.get_currency_by_code(code)?.successor()?)
+    }
+
+    /// Follows [`Currencies::successor`] to the end of the chain, e.g. from
+    /// `ZWD` through `ZWL` to `ZWG`.
+    pub fn latest_successor(&self, code: &str) -> Option<&Arc<Currency>> {
+        let mut latest = self.successor(code)?;
+        // 防止数据中的循环导致死循环
+        for _ in 0..self.len() {
+            match self.successor(&latest.code) {
+                Some(next) => latest = next,
+                None => break,
+            }
+        }
+        Some(latest)
+    }
+
+    /// Looks up a currency by an alias registered with
+    /// [`Currencies::add_alias`], in any case.
+    pub fn get_currency_by_alias(&self, alias: &str) -> Option<&Arc<Currency>> {
+        let code = self.aliases.get(upper_case(alias).as_ref())?;
+        self.get_currency_by_code(code)
+    }
+
+    /// Makes `alias` an alternative name for the currency `code`, such as
+    /// `RMB` for `CNY`. The currency does not need to be present yet.
+    pub fn add_alias(&mut self, alias: &str, code: &str) {
+        self.aliases.insert(alias.to_uppercase(), code.to_uppercase());
+    }
+
+    pub fn remove_alias(&mut self, alias: &str) -> Option<String> {
+        self.aliases.remove(upper_case(alias).as_ref())
+    }
+
+    /// Adds a currency, replacing any currency with the same code.
+    pub fn add(&mut self, currency: Currency) {
+        self.add_shared(Arc::new(currency));
+    }
+
+    /// Removes a currency, returning it. Once the returned `Arc` and any
+    /// others handed out for it are dropped, the currency is freed.
+    pub fn remove(&mut self, code: &str) -> Option<Arc<Currency>> {
+        let code = upper_case(code);
+        if let Some(currency) = self.historical.remove(code.as_ref()) {
+            if let Some(candidates) = currency.numeric().and_then(|numeric| self.historical_by_numeric.get_mut(&numeric)) {
+                candidates.retain(|c| !Arc::ptr_eq(c, &currency));
+                if candidates.is_empty() {
+                    self.historical_by_numeric.remove(&currency.numeric().unwrap());
+                }
+            }
+            return Some(currency);
+        }
+        let currency = self.currencies.remove(code.as_ref())?;
+        self.unindex(&currency);
+        Some(currency)
+    }
+
+    pub(crate) fn add_shared(&mut self, currency: Arc<Currency>) {
+        self.remove(&currency.code);
+        if currency.is_withdrawn() {
+            if let Some(numeric) = currency.numeric() {
+                let candidates = self.historical_by_numeric.entry(numeric).or_default();
+                let at = candidates.partition_point(|c| c.withdrawn <= currency.withdrawn);
+                candidates.insert(at, Arc::clone(&currency));
+            }
+            self.historical.insert(currency.code.clone(), currency);
+            return;
+        }
+        if let Some(numeric) = currency.numeric() {
+            self.by_numeric.entry(numeric).or_default().push(Arc::clone(&currency));
+        }
+        if !currency.grapheme.is_empty() {
+            self.by_grapheme.entry(currency.grapheme().to_string()).or_default().push(Arc::clone(&currency));
+        }
+        self.currencies.insert(currency.code.clone(), currency);
+    }
+
+    // 从数字代码和符号索引中移除，同一索引项下的其他货币保留
+    fn unindex(&mut self, currency: &Arc<Currency>) {
+        if let Some(candidates) = currency.numeric().and_then(|numeric| self.by_numeric.get_mut(&numeric)) {
+            candidates.retain(|c| !Arc::ptr_eq(c, currency));
+            if candidates.is_empty() {
+                self.by_numeric.remove(&currency.numeric().unwrap());
+            }
+        }
+        if let Some(candidates) = self.by_grapheme.get_mut(currency.grapheme()) {
+            candidates.retain(|c| !Arc::ptr_eq(c, currency));
+            if candidates.is_empty() {
+                self.by_grapheme.remove(currency.grapheme());
+            }
+        }
+    }
+
+    fn add_bulk(&mut self, currencies: impl IntoIterator<Item = Arc<Currency>>) {
+        for currency in currencies {
+            self.add_shared(currency);
+        }
+    }
+
+}
+
+// 代码通常已经是大写，此时查找不需要分配
+fn upper_case(code: &str) -> Cow<'_, str> {
+    if code.bytes().any(|b| b.is_ascii_lowercase()) || !code.is_ascii() {
+        Cow::Owned(code.to_uppercase())
+    } else {
+        Cow::Borrowed(code)
+    }
+}
+
+/// The alphabetic code and number of decimal places of a currency, which
+/// is all [`Money`](crate::Money) keeps of it; the rest of the definition is
+/// looked up in the registry when needed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Code {
+    code: [u8; 3],
+    fraction: u8,
+}
+
+impl Code {
+    // 三个 ASCII 字母或数字，统一为大写
+    fn parse(code: &str) -> Option<[u8; 3]> {
+        let bytes: [u8; 3] = code.as_bytes().try_into().ok()?;
+        bytes.iter().all(u8::is_ascii_alphanumeric).then(|| bytes.map(|b| b.to_ascii_uppercase()))
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.code).unwrap()
+    }
+
+    pub(crate) fn fraction(self) -> u32 {
+        self.fraction as u32
+    }
+
+    // 注册表中小数位数相同的定义；已被移除或已改为其他小数位数时退回内置定义，
+    // 两者都不可用时（例如货币只在另一线程的作用域注册表中）用以代码为符号的简单定义
+    pub(crate) fn currency(self) -> Arc<Currency> {
+        let matches = |c: &Arc<Currency>| c.fraction == self.fraction as usize;
+        Currencies::lookup(self.as_str())
+            .filter(matches)
+            .or_else(|| self.as_str().parse::<CurrencyCode>().ok().map(CurrencyCode::shared).filter(matches))
+            .unwrap_or_else(|| {
+                let code = self.as_str();
+                Arc::new(Currency::new_all(code, code.to_string(), "", "1 $", ".", ",", self.fraction as usize))
+            })
+    }
+}
+
+// 内置定义的代码和小数位数，不经过注册表
+impl From<CurrencyCode> for Code {
+    fn from(code: CurrencyCode) -> Code {
+        code.builtin().key()
+    }
+}
+
+impl fmt::Debug for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Default for Currencies {
+    fn default() -> Self {
+        let mut currencies = Currencies::new();
+        currencies.add_bulk(CurrencyCode::VARIANTS.iter().map(|c| c.shared()));
+        for (alias, code) in BUILTIN_ALIASES {
+            currencies.add_alias(alias, code);
+        }
+        currencies
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency_get(){
+        let currencies = Currencies::default();
+        let option = currencies.get_currency_by_code("EUR");
+        println!("{:?}", option);
+    }
+    #[test]
+    fn test_currency_add(){
+        let mut currencies = Currencies::default();
+        let currency = Currency::new_all("ssdfd","sss","","","","",32);
+        currencies.add(currency);
+        let option = currencies.get_currency_by_code("ssdfd");
+        println!("{:?}", option);
+    }
+    #[test]
+    fn test_currency_equals(){
+        let currencies = Currencies::default();
+        let option = currencies.get_currency_by_code("EUR").unwrap();
+        let option2 = currencies.get_currency_by_code("USD").unwrap();
+        let option3 = currencies.get_currency_by_code("EUR").unwrap();
+        println!("{:?}", option.eq(option2));
+        println!("{:?}", option.eq(option3));
+    }
+    #[test]
+    fn test_indexes(){
+        let mut currencies = Currencies::default();
+        assert_eq!(currencies.currency_by_numeric(8).unwrap().code(), "ALL");
+        assert_eq!(currencies.currency_by_numeric_code("008").unwrap().code(), "ALL");
+        assert!(currencies.currency_by_numeric_code("").is_none());
+        let dollars: Vec<&str> = currencies.currencies_by_grapheme("$").iter().map(|c| c.code()).collect();
+        assert!(["ARS", "AUD", "CAD", "USD"].iter().all(|code| dollars.contains(code)));
+        assert!(currencies.currencies_by_grapheme("nope").is_empty());
+
+        // 替换已有货币时旧的索引项被移除
+        currencies.add(Currency::new_all("USD", "US$", "840", "$1", ".", ",", 2));
+        assert!(!currencies.currencies_by_grapheme("$").iter().any(|c| c.code() == "USD"));
+        assert_eq!(currencies.currencies_by_grapheme("US$")[0].code(), "USD");
+        assert_eq!(currencies.currency_by_numeric(840).unwrap().grapheme(), "US$");
+
+        // 共用数字代码时，只移除指向被删货币的索引项
+        currencies.add(Currency::new_all("XTS", "¤", "963", "1 $", ".", ",", 2));
+        currencies.add(Currency::new_all("XTT", "¤", "963", "1 $", ".", ",", 2));
+        assert_eq!(currencies.currency_by_numeric(963).unwrap().code(), "XTT");
+        currencies.remove("XTS");
+        assert_eq!(currencies.currency_by_numeric(963).unwrap().code(), "XTT");
+        currencies.remove("xtt");
+        assert!(currencies.currency_by_numeric(963).is_none());
+        assert!(currencies.currencies_by_grapheme("¤").is_empty());
+
+        // 先移除后加入的货币时，先加入的仍可按数字代码找到
+        currencies.add(Currency::new_all("XTS", "¤", "963", "1 $", ".", ",", 2));
+        currencies.add(Currency::new_all("XTT", "¤", "963", "1 $", ".", ",", 2));
+        currencies.remove("XTT");
+        assert_eq!(currencies.currency_by_numeric(963).unwrap().code(), "XTS");
+        assert_eq!(currencies.currencies_by_grapheme("¤")[0].code(), "XTS");
+        currencies.remove("XTS");
+        assert!(currencies.currency_by_numeric(963).is_none());
+    }
+    #[test]
+    fn test_replaced_currency_is_freed(){
+        let mut currencies = Currencies::default();
+        currencies.add(Currency::new_all("XTS", "¤", "963", "1 $", ".", ",", 2));
+        let old = Arc::downgrade(currencies.get_currency_by_code("XTS").unwrap());
+        currencies.add(Currency::new_all("XTS", "¤", "963", "1 $", ".", ",", 3));
+        // 替换后表和索引中不再有旧定义的引用
+        assert!(old.upgrade().is_none());
+        let new = Arc::downgrade(currencies.get_currency_by_code("XTS").unwrap());
+        let removed = currencies.remove("XTS").unwrap();
+        assert_eq!(removed.fraction(), 3);
+        drop(removed);
+        assert!(new.upgrade().is_none());
+    }
+    #[test]
+    fn test_aliases(){
+        let mut currencies = Currencies::default();
+        assert_eq!(currencies.get_currency_by_alias("Rmb").unwrap().code(), "CNY");
+        assert!(currencies.get_currency_by_alias("CNY").is_none());
+        currencies.add_alias("quid", "gbp");
+        assert_eq!(currencies.get_currency_by_alias("QUID").unwrap().code(), "GBP");
+        currencies.remove("GBP");
+        assert!(currencies.get_currency_by_alias("quid").is_none());
+        assert_eq!(currencies.remove_alias("Quid"), Some("GBP".to_string()));
+    }
+    #[test]
+    fn test_historical(){
+        let mut currencies = Currencies::default();
+        assert!(currencies.historical().any(|c| c.code() == "DEM"));
+        assert!(currencies.historical().all(|c| c.is_withdrawn()));
+        for (code, successor) in [("VEF", "VES"), ("BYR", "BYN"), ("ZWD", "ZWL"), ("DEM", "EUR"), ("FRF", "EUR")] {
+            assert_eq!(currencies.successor(code).unwrap().code(), successor);
+        }
+        let frf = currencies.get_currency_by_code("frf").unwrap();
+        assert_eq!((frf.name(), frf.withdrawn().unwrap().year()), ("French franc", 2002));
+        assert_eq!(currencies.currency_by_numeric(276).unwrap().code(), "DEM");
+        assert!(!currencies.currencies_by_grapheme("DM").iter().any(|c| c.code() == "DEM"));
+
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        let active = currencies.active_on(date("2001-06-15"));
+        assert!(active.iter().any(|c| c.code() == "DEM") && active.iter().any(|c| c.code() == "EUR"));
+        assert!(!active.iter().any(|c| c.code() == "BYN"));
+        assert!(active.windows(2).all(|w| w[0].code() < w[1].code()));
+        assert!(!currencies.active_on(date("2002-03-01")).iter().any(|c| c.code() == "FRF"));
+
+        // 设置停用日期后转入历史表
+        let withdrawn = Currency::from_code("SLL").unwrap().with_withdrawn(date("2024-01")).with_successor("sle");
+        currencies.add(withdrawn);
+        assert!(currencies.historical().any(|c| c.code() == "SLL"));
+        assert_eq!(currencies.successor("SLL").unwrap().code(), "SLE");
+        assert_eq!(currencies.len(), CurrencyCode::VARIANTS.len());
+        assert!(currencies.remove("SLL").unwrap().is_withdrawn());
+        assert!(currencies.get_currency_by_code("SLL").is_none());
+    }
+    #[test]
+    fn test_historical_numeric_index(){
+        // 与 CurrencyCode::from_numeric 的选择一致
+        let mut currencies = Currencies::default();
+        for &code in CurrencyCode::VARIANTS {
+            let Some(numeric) = code.numeric() else { continue };
+            assert_eq!(currencies.currency_by_numeric(numeric).unwrap().code(), CurrencyCode::from_numeric(numeric).unwrap().as_str());
+        }
+
+        // 按停用日期而不是加入顺序选择，移除后退回到其他已停用的货币
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        let withdrawn = |code: &str, on: &str| Currency::new_all(code, "¤", "963", "1 $", ".", ",", 2).with_withdrawn(date(on));
+        currencies.add(withdrawn("XTT", "2010-01"));
+        currencies.add(withdrawn("XTS", "2000-01"));
+        currencies.add(withdrawn("XTU", "2005-01"));
+        assert_eq!(currencies.currency_by_numeric(963).unwrap().code(), "XTT");
+        currencies.remove("XTT");
+        assert_eq!(currencies.currency_by_numeric(963).unwrap().code(), "XTU");
+        // 现行货币优先
+        currencies.add(Currency::new_all("XTV", "¤", "963", "1 $", ".", ",", 2));
+        assert_eq!(currencies.currency_by_numeric(963).unwrap().code(), "XTV");
+        currencies.remove("XTV");
+        currencies.remove("XTU");
+        currencies.add(withdrawn("XTS", "2001-01"));
+        assert_eq!(currencies.currency_by_numeric(963).unwrap().withdrawn(), Some(date("2001-01")));
+        currencies.remove("XTS");
+        assert!(currencies.currency_by_numeric(963).is_none());
+    }
+}
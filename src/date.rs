@@ -0,0 +1,115 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::MoneyError;
+
+/// A calendar date, used for the period in which a currency is in use.
+///
+/// ISO 4217 records introductions and withdrawals to the month; such dates
+/// are parsed as the first day of the month.
+///
+/// ```
+/// use rust_money::Date;
+///
+/// let d: Date = "2002-03".parse().unwrap();
+/// assert_eq!(d, Date::new(2002, 3, 1).unwrap());
+/// assert_eq!(d.to_string(), "2002-03-01");
+/// assert!("2002-02-30".parse::<Date>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    pub fn new(year: u16, month: u8, day: u8) -> Result<Date, MoneyError> {
+        let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return Err(MoneyError::InvalidDate),
+        };
+        if day == 0 || day > days {
+            return Err(MoneyError::InvalidDate);
+        }
+        Ok(Date { year, month, day })
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Parses `YYYY-MM-DD` or `YYYY-MM`.
+impl FromStr for Date {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // 各部分必须是固定位数的数字
+        let number = |part: &str, len: usize| -> Result<u16, MoneyError> {
+            if part.len() != len || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(MoneyError::InvalidDate);
+            }
+            part.parse().map_err(|_| MoneyError::InvalidDate)
+        };
+        let mut parts = s.split('-');
+        let year = number(parts.next().unwrap_or_default(), 4)?;
+        let month = number(parts.next().ok_or(MoneyError::InvalidDate)?, 2)?;
+        let day = parts.next().map_or(Ok(1), |day| number(day, 2))?;
+        if parts.next().is_some() {
+            return Err(MoneyError::InvalidDate);
+        }
+        Date::new(year, month as u8, day as u8)
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = std::borrow::Cow::<str>::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("2024-02-29".parse::<Date>(), Date::new(2024, 2, 29));
+        assert_eq!("1900-02-29".parse::<Date>(), Err(MoneyError::InvalidDate));
+        assert_eq!("2000-02-29".parse::<Date>().unwrap().to_string(), "2000-02-29");
+        for s in ["", "2002", "2002-3", "2002-13", "2002-03-01-01", "02002-03", "2002-0a", "2002-03-"] {
+            assert_eq!(s.parse::<Date>(), Err(MoneyError::InvalidDate), "{}", s);
+        }
+        assert!(Date::new(2002, 3, 1).unwrap() < Date::new(2002, 3, 2).unwrap());
+        assert_eq!(serde_json::to_string(&Date::new(1999, 1, 1).unwrap()).unwrap(), r#""1999-01-01""#);
+    }
+}
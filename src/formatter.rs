@@ -1,68 +1,564 @@
-use std::cmp;
-use std::fmt;
-
-#[derive(Debug, Clone)]
-pub struct Formatter {
-    pub fraction: usize,
-    pub decimal: String,
-    pub thousand: String,
-    pub grapheme: String,
-    pub template: String,
-}
-
-impl Formatter {
-    pub fn new(fraction: usize, decimal: &str, thousand: &str, grapheme: &str, template: &str) -> Self {
-        Self {
-            fraction,
-            decimal: decimal.to_string(),
-            thousand: thousand.to_string(),
-            grapheme: grapheme.to_string(),
-            template: template.to_string(),
-        }
-    }
-
-    pub fn format(&self, amount: i64) -> String {
-        // Work with absolute amount value
-        let mut sa = self.abs(amount).to_string();
-
-        if sa.len() <= self.fraction {
-            sa = "0".repeat(self.fraction - sa.len() + 1) + &sa;
-        }
-
-        if !self.thousand.is_empty() {
-            let mut i = sa.len() as isize - self.fraction as isize - 3;
-            while i > 0 {
-                let i_usize = i as usize;
-                sa.insert_str(i_usize, &self.thousand);
-                i -= 3;
-            }
-        }
-
-        if self.fraction > 0 {
-            let len = sa.len();
-            sa.insert(len - self.fraction, self.decimal.chars().next().unwrap());
-        }
-
-        let mut result = self.template.replace('1', &sa);
-        result = result.replace('$', &self.grapheme);
-
-        // Add minus sign for negative amount.
-        if amount < 0 {
-            result = "-".to_string() + &result;
-        }
-
-        result
-    }
-
-    pub fn to_major_units(&self, amount: i64) -> f64 {
-        if self.fraction == 0 {
-            return amount as f64;
-        }
-
-        amount as f64 / 10_f64.powi(self.fraction as i32)
-    }
-
-    fn abs(&self, amount: i64) -> i64 {
-        cmp::max(amount, -amount)
-    }
-}
\ No newline at end of file
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::rounding::{self, RoundingMode};
+use crate::MoneyError;
+
+/// Renders minor-unit amounts using a currency's separators, symbol and template.
+///
+/// In the template `1` stands for the amount and `$` for the symbol.
+/// Negative amounts get a leading `-`, unless the template has a second
+/// part after a `;` to use for them instead, as in `"$ 1;$-1"`. Digits are
+/// written in the [`Digits`] numbering system, ASCII by default.
+#[derive(Debug, Clone)]
+pub struct Formatter {
+    pub fraction: usize,
+    pub decimal: String,
+    pub thousand: String,
+    pub grapheme: String,
+    pub template: String,
+    pub grouping: Grouping,
+    pub digits: Digits,
+}
+
+/// The sizes of the digit groups that `thousand` separates, counted from
+/// the decimal point: one `primary` group, then `secondary` ones.
+///
+/// It reads and prints as the integer part of a CLDR number pattern:
+///
+/// ```
+/// use rust_money::Grouping;
+///
+/// assert_eq!("#,##,##0.00".parse(), Ok(Grouping::INDIAN));
+/// assert_eq!("¤#,##0.00".parse(), Ok(Grouping::THOUSANDS));
+/// assert_eq!(Grouping::MYRIAD.to_string(), "#,###0");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Grouping {
+    pub primary: usize,
+    pub secondary: usize,
+}
+
+impl Grouping {
+    /// Groups of three: `1,000,000`.
+    pub const THOUSANDS: Grouping = Grouping { primary: 3, secondary: 3 };
+    /// Indian lakh and crore: `1,00,00,000`.
+    pub const INDIAN: Grouping = Grouping { primary: 3, secondary: 2 };
+    /// East Asian myriads (万, 億): `1,0000,0000`.
+    pub const MYRIAD: Grouping = Grouping { primary: 4, secondary: 4 };
+
+    // 从左到右的各组位数是否符合分组规则：首组一到 secondary 位，最后一组 primary 位，其余 secondary 位
+    pub(crate) fn accepts(&self, groups: &[usize]) -> bool {
+        match groups {
+            [] | [_] => true,
+            [first, middle @ .., last] => {
+                (1..=self.secondary).contains(first) && middle.iter().all(|g| *g == self.secondary) && *last == self.primary
+            }
+        }
+    }
+}
+
+impl Default for Grouping {
+    fn default() -> Self {
+        Grouping::THOUSANDS
+    }
+}
+
+impl fmt::Display for Grouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |n: usize| "#".repeat(n);
+        f.write_str("#")?;
+        if self.secondary != self.primary {
+            write!(f, ",{}", digits(self.secondary))?;
+        }
+        write!(f, ",{}0", digits(self.primary.saturating_sub(1)))
+    }
+}
+
+/// Parses a CLDR number pattern such as `"#,##,##0.00"`; anything around
+/// the digits, like `¤`, is ignored. The pattern must contain a grouping
+/// separator.
+impl FromStr for Grouping {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let start = s.find(['#', '0']).ok_or(ParseError::InvalidGrouping)?;
+        let integer = s[start..].split('.').next().unwrap();
+        let integer = integer.trim_end_matches(|c| !matches!(c, '#' | '0'));
+        let sizes: Vec<usize> = integer.split(',').map(str::len).collect();
+        if sizes.len() < 2 || sizes.contains(&0) || !integer.chars().all(|c| matches!(c, '#' | '0' | ',')) {
+            return Err(ParseError::InvalidGrouping);
+        }
+        let primary = sizes[sizes.len() - 1];
+        let secondary = if sizes.len() > 2 { sizes[sizes.len() - 2] } else { primary };
+        Ok(Grouping { primary, secondary })
+    }
+}
+
+impl Serialize for Grouping {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Grouping {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = std::borrow::Cow::<str>::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// The numbering system whose ten digits an amount is written in, named
+/// after the CLDR numbering systems.
+///
+/// ```
+/// use rust_money::{Digits, Formatter};
+///
+/// let f = Formatter::new(2, "٫", "٬", "ر.س", "1 $").with_digits(Digits::ArabicIndic);
+/// assert_eq!(f.format(123456), "١٬٢٣٤٫٥٦ ر.س");
+/// assert_eq!(Digits::Thai.digit(7), '๗');
+/// assert_eq!(Digits::ExtendedArabicIndic.value('۷'), Some(7));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Digits {
+    /// ASCII `0123456789` (`latn`).
+    #[default]
+    Latin,
+    /// `٠١٢٣٤٥٦٧٨٩`, used with Arabic (`arab`).
+    ArabicIndic,
+    /// `۰۱۲۳۴۵۶۷۸۹`, used with Persian and Urdu (`arabext`).
+    ExtendedArabicIndic,
+    /// `०१२३४५६७८९` (`deva`).
+    Devanagari,
+    /// `০১২৩৪৫৬৭৮৯` (`beng`).
+    Bengali,
+    /// `๐๑๒๓๔๕๖๗๘๙` (`thai`).
+    Thai,
+    /// `０１２３４５６７８９` (`fullwide`).
+    FullWidth,
+}
+
+impl Digits {
+    fn zero(&self) -> char {
+        match self {
+            Digits::Latin => '0',
+            Digits::ArabicIndic => '\u{660}',
+            Digits::ExtendedArabicIndic => '\u{6f0}',
+            Digits::Devanagari => '\u{966}',
+            Digits::Bengali => '\u{9e6}',
+            Digits::Thai => '\u{e50}',
+            Digits::FullWidth => '\u{ff10}',
+        }
+    }
+
+    /// The character for `digit`, which must be less than 10.
+    pub fn digit(&self, digit: u32) -> char {
+        assert!(digit < 10, "not a decimal digit: {}", digit);
+        char::from_u32(self.zero() as u32 + digit).unwrap()
+    }
+
+    /// The value of `c` if it is one of these digits.
+    pub fn value(&self, c: char) -> Option<u32> {
+        (c as u32).checked_sub(self.zero() as u32).filter(|d| *d < 10)
+    }
+
+    // 按 CLDR 数字系统的名称查找，如 "arabext"
+    pub(crate) fn from_cldr(name: &str) -> Option<Digits> {
+        match name {
+            "latn" => Some(Digits::Latin),
+            "arab" => Some(Digits::ArabicIndic),
+            "arabext" => Some(Digits::ExtendedArabicIndic),
+            "deva" => Some(Digits::Devanagari),
+            "beng" => Some(Digits::Bengali),
+            "thai" => Some(Digits::Thai),
+            "fullwide" => Some(Digits::FullWidth),
+            _ => None,
+        }
+    }
+}
+
+impl Formatter {
+    pub fn new(fraction: usize, decimal: &str, thousand: &str, grapheme: &str, template: &str) -> Self {
+        Self {
+            fraction,
+            decimal: decimal.to_string(),
+            thousand: thousand.to_string(),
+            grapheme: grapheme.to_string(),
+            template: template.to_string(),
+            grouping: Grouping::default(),
+            digits: Digits::default(),
+        }
+    }
+
+    /// Sets the digit grouping, e.g. lakh and crore for `INR` or myriads
+    /// for `JPY`:
+    ///
+    /// ```
+    /// use rust_money::{Formatter, Grouping};
+    ///
+    /// let f = Formatter::new(0, ".", ",", "¥", "$1").with_grouping(Grouping::MYRIAD);
+    /// assert_eq!(f.format(123456789), "¥1,2345,6789");
+    /// assert_eq!(f.parse("¥1,2345,6789"), Ok(123456789));
+    /// ```
+    pub fn with_grouping(mut self, grouping: Grouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Sets the digits to format with. Parsing accepts them as well as
+    /// ASCII digits:
+    ///
+    /// ```
+    /// use rust_money::{Digits, Formatter};
+    ///
+    /// let f = Formatter::new(2, ".", ",", "₹", "$1").with_digits(Digits::Devanagari);
+    /// assert_eq!(f.format(-123456), "-₹१,२३४.५६");
+    /// assert_eq!(f.parse("₹१,२३४.५६"), Ok(123456));
+    /// assert_eq!(f.parse("₹1,234.56"), Ok(123456));
+    /// ```
+    pub fn with_digits(mut self, digits: Digits) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    /// Formats an amount given in minor units.
+    ///
+    /// ```
+    /// use rust_money::Formatter;
+    ///
+    /// let f = Formatter::new(2, ".", ",", "$", "$1");
+    /// assert_eq!(f.format(-123456), "-$1,234.56");
+    /// ```
+    pub fn format(&self, amount: i64) -> String {
+        // Work with absolute amount value
+        let mut sa = amount.unsigned_abs().to_string();
+
+        if sa.len() <= self.fraction {
+            sa = "0".repeat(self.fraction - sa.len() + 1) + &sa;
+        }
+
+        // 分组大小为 0 时不分组
+        if !self.thousand.is_empty() && self.grouping.primary > 0 && self.grouping.secondary > 0 {
+            let mut i = sa.len() as isize - self.fraction as isize - self.grouping.primary as isize;
+            while i > 0 {
+                let i_usize = i as usize;
+                sa.insert_str(i_usize, &self.thousand);
+                i -= self.grouping.secondary as isize;
+            }
+        }
+
+        if self.fraction > 0 {
+            let len = sa.len();
+            sa.insert(len - self.fraction, self.decimal.chars().next().unwrap());
+        }
+
+        // 分组和小数点按字节位置插入，数字最后再换成目标数字系统
+        if self.digits != Digits::Latin {
+            sa = sa.chars().map(|c| c.to_digit(10).filter(|_| c.is_ascii_digit()).map_or(c, |d| self.digits.digit(d))).collect();
+        }
+
+        let (positive, negative) = self.templates();
+        let template = if amount < 0 { negative.unwrap_or(positive) } else { positive };
+        let mut result = template.replace('1', &sa);
+        result = result.replace('$', &self.grapheme);
+
+        // Add minus sign for negative amount.
+        if amount < 0 && negative.is_none() {
+            result = "-".to_string() + &result;
+        }
+
+        result
+    }
+
+    /// Parses a string produced by [`format`](Formatter::format) back into
+    /// minor units, without going through `f64`.
+    ///
+    /// The symbol and the thousand separators may be left out, but where
+    /// they appear they must be where the template puts them. Fraction
+    /// digits beyond the currency's fraction are only accepted if they are
+    /// zero; see [`parse_with`](Formatter::parse_with) to round them.
+    ///
+    /// ```
+    /// use rust_money::{Formatter, ParseError};
+    ///
+    /// let f = Formatter::new(2, ".", ",", "$", "$1");
+    /// assert_eq!(f.parse("-$1,234.56"), Ok(-123456));
+    /// assert_eq!(f.parse("1234.5"), Ok(123450));
+    /// assert_eq!(f.parse("$1.999"), Err(ParseError::ExcessFractionDigits));
+    ///
+    /// let f = Formatter::new(2, ",", ".", "€", "1 $");
+    /// assert_eq!(f.parse("1.234,56 €"), Ok(123456));
+    /// ```
+    pub fn parse(&self, input: &str) -> Result<i64, ParseError> {
+        self.parse_with(input, RoundingMode::Unnecessary)
+    }
+
+    /// Like [`parse`](Formatter::parse), but rounds excess fraction digits
+    /// with `mode`.
+    ///
+    /// ```
+    /// use rust_money::{Formatter, RoundingMode};
+    ///
+    /// let f = Formatter::new(2, ".", ",", "$", "$1");
+    /// assert_eq!(f.parse_with("$1.995", RoundingMode::HalfEven), Ok(200));
+    /// assert_eq!(f.parse_with("-$1.999", RoundingMode::TowardZero), Ok(-199));
+    /// ```
+    pub fn parse_with(&self, input: &str, mode: RoundingMode) -> Result<i64, ParseError> {
+        let trimmed = input.trim_start();
+        let mut offset = input.len() - trimmed.len();
+        let mut text = trimmed.trim_end();
+
+        // 负数模板须完整匹配，符号不能省略
+        if let Some((prefix, suffix)) = self.templates().1.map(|t| self.affixes(t)) {
+            if let Some(rest) = text.strip_prefix(prefix.as_str()).and_then(|t| t.strip_suffix(suffix.as_str())) {
+                let (mantissa, scale) = self.parse_number(rest, offset + prefix.len())?;
+                return self.to_minor_units(-mantissa, scale, mode);
+            }
+        }
+
+        // 负号在模板之外，与 format 一致
+        let negative = match text.strip_prefix('-') {
+            Some(rest) => {
+                offset += 1;
+                text = rest;
+                true
+            }
+            None => false,
+        };
+
+        // 模板以 '1' 为界分成前后两段，符号可以省略
+        let (prefix, suffix) = self.affixes(self.templates().0);
+        if let Some(rest) = text.strip_prefix(prefix.as_str()).filter(|_| !prefix.is_empty()) {
+            offset += prefix.len();
+            text = rest;
+        }
+        if let Some(rest) = text.strip_suffix(suffix.as_str()).filter(|_| !suffix.is_empty()) {
+            text = rest;
+        }
+
+        let (mantissa, scale) = self.parse_number(text, offset)?;
+        self.to_minor_units(if negative { -mantissa } else { mantissa }, scale, mode)
+    }
+
+    // 正数模板和可选的负数模板
+    fn templates(&self) -> (&str, Option<&str>) {
+        match self.template.split_once(';') {
+            Some((positive, negative)) => (positive, Some(negative)),
+            None => (&self.template, None),
+        }
+    }
+
+    // 模板中金额前后的文字，符号已替换
+    fn affixes(&self, template: &str) -> (String, String) {
+        let (prefix, suffix) = template.split_once('1').unwrap_or(("", ""));
+        (prefix.replace('$', &self.grapheme), suffix.replace('$', &self.grapheme))
+    }
+
+    fn to_minor_units(&self, mantissa: i128, scale: u32, mode: RoundingMode) -> Result<i64, ParseError> {
+        rounding::rescale(mantissa, scale, self.fraction as u32, mode)
+            .and_then(rounding::to_i64)
+            .map_err(|err| match err {
+                MoneyError::RoundingNecessary => ParseError::ExcessFractionDigits,
+                _ => ParseError::Overflow,
+            })
+    }
+
+    // 解析去掉符号和模板后的数字部分，返回 (mantissa, scale)
+    fn parse_number(&self, text: &str, offset: usize) -> Result<(i128, u32), ParseError> {
+        let decimal = self.decimal.chars().next();
+        let (int_part, frac_part) = match decimal.and_then(|d| text.find(d).map(|i| (i, d))) {
+            Some((i, d)) => (&text[..i], &text[i + d.len_utf8()..]),
+            None => (text, ""),
+        };
+
+        let mut mantissa: i128 = 0;
+        let mut digits = 0;
+        // 各组的位数，最后一并按 grouping 检查
+        let mut groups = vec![0];
+        let mut i = 0;
+        while i < int_part.len() {
+            let rest = &int_part[i..];
+            if !self.thousand.is_empty() && rest.starts_with(self.thousand.as_str()) {
+                groups.push(0);
+                i += self.thousand.len();
+                continue;
+            }
+            let c = rest.chars().next().unwrap();
+            mantissa = push_digit(mantissa, c, self.digits, offset + i)?;
+            digits += 1;
+            *groups.last_mut().unwrap() += 1;
+            i += c.len_utf8();
+        }
+        if !self.grouping.accepts(&groups) {
+            return Err(ParseError::InvalidGrouping);
+        }
+
+        if digits == 0 && frac_part.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        // 末尾的 0 不影响数值，去掉以免无谓的溢出
+        let frac_offset = offset + text.len() - frac_part.len();
+        let frac_part = frac_part.trim_end_matches('0');
+        let mut scale = 0;
+        for (i, c) in frac_part.char_indices() {
+            mantissa = push_digit(mantissa, c, self.digits, frac_offset + i)?;
+            scale += 1;
+        }
+
+        Ok((mantissa, scale))
+    }
+
+    pub fn to_major_units(&self, amount: i64) -> f64 {
+        if self.fraction == 0 {
+            return amount as f64;
+        }
+
+        amount as f64 / 10_f64.powi(self.fraction as i32)
+    }
+}
+
+fn push_digit(mantissa: i128, c: char, digits: Digits, position: usize) -> Result<i128, ParseError> {
+    let digit = Digits::Latin
+        .value(c)
+        .or_else(|| digits.value(c))
+        .ok_or(ParseError::UnexpectedCharacter { position, found: c })?;
+    mantissa
+        .checked_mul(10)
+        .and_then(|m| m.checked_add(digit as i128))
+        .ok_or(ParseError::Overflow)
+}
+
+/// The reason a string could not be parsed as an amount of money.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input contains no digits.
+    Empty,
+    /// A character the format does not allow, at the given byte offset.
+    UnexpectedCharacter { position: usize, found: char },
+    /// Thousand separators that do not split the integer part into the
+    /// expected groups, or an invalid [`Grouping`] pattern.
+    InvalidGrouping,
+    /// Non-zero digits beyond the currency's fraction, with no rounding mode to drop them.
+    ExcessFractionDigits,
+    /// The amount does not fit in an `i64` of minor units.
+    Overflow,
+    /// The currency code is not registered.
+    UnknownCurrency(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "No amount to parse"),
+            ParseError::UnexpectedCharacter { position, found } => {
+                write!(f, "Unexpected character `{}` at position {}", found, position)
+            }
+            ParseError::InvalidGrouping => write!(f, "Invalid digit grouping"),
+            ParseError::ExcessFractionDigits => write!(f, "Too many fraction digits"),
+            ParseError::Overflow => write!(f, "Amount overflow"),
+            ParseError::UnknownCurrency(code) => write!(f, "Unknown currency code `{}`", code),
+        }
+    }
+}
+
+impl Error for ParseError {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CurrencyCode;
+
+    #[test]
+    fn test_parse_round_trip() {
+        for code in CurrencyCode::VARIANTS {
+            let f = code.builtin().formatter();
+            for amount in [0, 1, -1, 99, 1000, -123456, 1234567890, i64::MAX, i64::MIN] {
+                assert_eq!(f.parse(&f.format(amount)), Ok(amount), "{} {}", code, f.format(amount));
+            }
+        }
+    }
+    #[test]
+    fn test_parse_errors() {
+        let f = Formatter::new(2, ".", ",", "$", "$1");
+        assert_eq!(f.parse(""), Err(ParseError::Empty));
+        assert_eq!(f.parse("$"), Err(ParseError::Empty));
+        assert_eq!(f.parse("$1,23.00"), Err(ParseError::InvalidGrouping));
+        assert_eq!(f.parse("1234,567"), Err(ParseError::InvalidGrouping));
+        assert_eq!(f.parse(",123"), Err(ParseError::InvalidGrouping));
+        assert_eq!(f.parse("12$"), Err(ParseError::UnexpectedCharacter { position: 2, found: '$' }));
+        assert_eq!(f.parse(" $1.2.3"), Err(ParseError::UnexpectedCharacter { position: 5, found: '.' }));
+        assert_eq!(f.parse("$0.001"), Err(ParseError::ExcessFractionDigits));
+        assert_eq!(f.parse("$1.50000000000000000000000000000000000000000"), Ok(150));
+        assert_eq!(f.parse("$92233720368547758.08"), Err(ParseError::Overflow));
+        assert_eq!(f.parse_with("$0.001", RoundingMode::Ceiling), Ok(1));
+
+        let f = Formatter::new(0, ".", " ", "Ft", "1 $");
+        assert_eq!(f.parse("-1 234 567 Ft"), Ok(-1234567));
+        assert_eq!(f.parse("12.00"), Ok(12));
+
+        let f = Formatter::new(2, ".", ",", "₹", "$1").with_grouping(Grouping::INDIAN);
+        assert_eq!(f.parse("₹1,234,567.00"), Err(ParseError::InvalidGrouping));
+        assert_eq!(f.parse("₹123,45,678.00"), Err(ParseError::InvalidGrouping));
+        assert_eq!(f.parse("₹12,34,567.00"), Ok(123456700));
+    }
+    #[test]
+    fn test_grouping() {
+        let inr = Formatter::new(2, ".", ",", "₹", "$1").with_grouping(Grouping::INDIAN);
+        assert_eq!(inr.format(-1_234_567_890), "-₹1,23,45,678.90");
+        assert_eq!(inr.format(9_999_900), "₹99,999.00");
+        assert_eq!(inr.format(10_000_000), "₹1,00,000.00");
+        let jpy = Formatter::new(0, ".", ",", "¥", "$1").with_grouping(Grouping::MYRIAD);
+        assert_eq!(jpy.format(1234), "¥1234");
+        assert_eq!(jpy.format(12345), "¥1,2345");
+        assert_eq!(jpy.format(i64::MAX), "¥922,3372,0368,5477,5807");
+        for f in [&inr, &jpy] {
+            for amount in [0, 12345, -123456789, i64::MAX, i64::MIN] {
+                assert_eq!(f.parse(&f.format(amount)), Ok(amount));
+            }
+        }
+        // 不分组的写法总是可以解析
+        assert_eq!(inr.parse("1234567.89"), Ok(123456789));
+        assert_eq!(Formatter::new(0, ".", "", "", "1").with_grouping(Grouping::INDIAN).format(1234567), "1234567");
+
+        assert_eq!("¤#,##,##0.00".parse(), Ok(Grouping::INDIAN));
+        assert_eq!("#,###0".parse(), Ok(Grouping::MYRIAD));
+        assert_eq!("#,##0.###".parse(), Ok(Grouping::THOUSANDS));
+        for pattern in ["#0.00", "", "¤", "#,,##0", ",##0"] {
+            assert_eq!(pattern.parse::<Grouping>(), Err(ParseError::InvalidGrouping), "{}", pattern);
+        }
+        for grouping in [Grouping::THOUSANDS, Grouping::INDIAN, Grouping::MYRIAD] {
+            assert_eq!(grouping.to_string().parse(), Ok(grouping));
+        }
+        assert_eq!(serde_json::to_string(&Grouping::INDIAN).unwrap(), r##""#,##,##0""##);
+    }
+    #[test]
+    fn test_digits() {
+        let all = [
+            Digits::Latin,
+            Digits::ArabicIndic,
+            Digits::ExtendedArabicIndic,
+            Digits::Devanagari,
+            Digits::Bengali,
+            Digits::Thai,
+            Digits::FullWidth,
+        ];
+        for digits in all {
+            let f = Formatter::new(2, ".", ",", "$", "$1").with_digits(digits);
+            for amount in [0, 7, -123456, i64::MAX, i64::MIN] {
+                assert_eq!(f.parse(&f.format(amount)), Ok(amount), "{:?}", digits);
+            }
+            assert_eq!((0..10).map(|d| digits.value(digits.digit(d))).collect::<Vec<_>>(), (0..10).map(Some).collect::<Vec<_>>());
+        }
+        let f = Formatter::new(0, ".", ",", "", "1").with_digits(Digits::Thai);
+        assert_eq!(f.format(1_234_567), "๑,๒๓๔,๕๖๗");
+        assert_eq!(f.parse("๑,234,๕๖๗"), Ok(1_234_567));
+        // 只接受 ASCII 和所设数字系统的数字
+        assert_eq!(f.parse("๑٢"), Err(ParseError::UnexpectedCharacter { position: 3, found: '٢' }));
+        assert_eq!(Formatter::new(0, ".", ",", "", "1").parse("٢"), Err(ParseError::UnexpectedCharacter { position: 0, found: '٢' }));
+        assert_eq!(Digits::Latin.value('٢'), None);
+    }
+}
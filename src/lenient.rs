@@ -0,0 +1,430 @@
+//! Lenient parsing of amounts typed by people.
+//!
+//! Unlike [`Formatter::parse`](crate::Formatter::parse), which expects the
+//! exact format of a known currency, [`Currencies::parse_lenient`] accepts
+//! whatever a user or a spreadsheet is likely to produce and reports how
+//! much it had to guess:
+//!
+//! ```
+//! use rust_money::{Confidence, Currencies, Rational};
+//!
+//! let currencies = Currencies::default();
+//! let parsed = currencies.parse_lenient("(1,234.00)").unwrap();
+//! assert_eq!(parsed.amount, Rational::from_integer(-1234));
+//! assert_eq!(parsed.confidence, Confidence::Certain);
+//!
+//! // A single separator followed by exactly three digits can be read either way
+//! let parsed = currencies.parse_lenient("12,345").unwrap();
+//! assert_eq!(parsed.amount, Rational::from_integer(12345));
+//! assert_eq!(parsed.alternative, Some("12.345".parse().unwrap()));
+//! assert_eq!(parsed.confidence, Confidence::Ambiguous);
+//! ```
+
+use std::sync::Arc;
+
+use crate::currency::{Currencies, Currency};
+use crate::formatter::{Grouping, ParseError};
+use crate::rational::Rational;
+use crate::rounding::{self, RoundingMode};
+use crate::{Money, MoneyError};
+
+// 只用于分组、不会是小数点的字符
+const GROUPING_ONLY: &[char] = &[' ', '\u{a0}', '\u{202f}', '\'', '’'];
+// 负号：ASCII 连字符、Unicode 减号和短破折号
+const MINUS: &[char] = &['-', '\u{2212}', '\u{2013}'];
+// 紧跟在数字后的数量级后缀，长的在前
+const MAGNITUDES: &[(&str, u32)] = &[("bn", 9), ("mn", 6), ("k", 3), ("K", 3), ("m", 6), ("M", 6)];
+
+/// How much guessing went into a [`LenientAmount`]. Ordered from least to
+/// most certain, so the confidence of a combined guess is the minimum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    /// More than one reading fits: see [`LenientAmount::alternative`] and
+    /// [`LenientAmount::currencies`].
+    Ambiguous,
+    /// The separators could be read either way, and the currency's format
+    /// decided between them.
+    Likely,
+    /// The input has only one sensible reading.
+    Certain,
+}
+
+/// The result of [`Currencies::parse_lenient`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LenientAmount {
+    /// The amount in major units, under the most likely reading of the
+    /// separators.
+    pub amount: Rational,
+    /// The other reading when the separators are ambiguous, e.g. `12.345`
+    /// for `"12,345"`.
+    pub alternative: Option<Rational>,
+    /// The currencies the symbol, code, alias or name in the input may
+    /// refer to, sorted by code. Empty if the input names no currency.
+    pub currencies: Vec<Arc<Currency>>,
+    pub confidence: Confidence,
+}
+
+impl LenientAmount {
+    /// Converts [`amount`](LenientAmount::amount) to money of the currency
+    /// `code`, rounding digits beyond its fraction with `mode`.
+    ///
+    /// ```
+    /// use rust_money::{Currencies, Money, RoundingMode};
+    ///
+    /// let parsed = Currencies::default().parse_lenient("US$1.5k").unwrap();
+    /// let code = parsed.currencies[0].code();
+    /// assert_eq!(parsed.to_money(code, RoundingMode::HalfUp), Ok(Money::new(150000, "USD")));
+    /// ```
+    pub fn to_money(&self, code: &str, mode: RoundingMode) -> Result<Money, MoneyError> {
+        let currency = Currency::resolve(code)?;
+        let numer = self
+            .amount
+            .numer()
+            .checked_mul(rounding::pow10(currency.fraction as u32)?)
+            .ok_or(MoneyError::Overflow)?;
+        let amount = rounding::to_i64(rounding::div_round(numer, self.amount.denom(), mode)?)?;
+        Ok(Money {
+            amount,
+            currency: currency.key(),
+        })
+    }
+}
+
+impl Currencies {
+    /// Parses a free-form amount such as `"1.5k"`, `"€ 12,-"`,
+    /// `"(1,234.00)"`, `"US$12"` or `"12.50 dollars"`.
+    ///
+    /// Decimal and thousand separators are inferred from their positions,
+    /// in groups of three or in Indian lakhs and crores. A single `.` or `,`
+    /// followed by exactly three digits can be either; the format of the
+    /// named currency decides if there is one, otherwise the result is
+    /// [`Confidence::Ambiguous`] with the decimal reading in
+    /// [`alternative`](LenientAmount::alternative).
+    ///
+    /// The currency may be given before or after the number as a code, an
+    /// alias, a symbol, a symbol with a country prefix (`US$`, `HK$`) or a
+    /// name (`euros`, `US dollar`). Negative amounts may use a leading or
+    /// trailing minus, `−` or accounting parentheses. A `k`, `m`/`mn` or
+    /// `bn` directly after the number multiplies it by a thousand, a
+    /// million or a billion.
+    ///
+    /// ```
+    /// use rust_money::{Confidence, Currencies, Rational};
+    ///
+    /// let currencies = Currencies::default();
+    /// let parsed = currencies.parse_lenient("€ 12,-").unwrap();
+    /// assert_eq!((parsed.amount, parsed.currencies[0].code()), (Rational::from_integer(12), "EUR"));
+    ///
+    /// let parsed = currencies.parse_lenient("12.50 dollars").unwrap();
+    /// assert_eq!(parsed.amount, "12.5".parse().unwrap());
+    /// assert!(parsed.currencies.len() > 1);
+    /// assert_eq!(parsed.confidence, Confidence::Ambiguous);
+    /// ```
+    pub fn parse_lenient(&self, input: &str) -> Result<LenientAmount, ParseError> {
+        let first = input.find(|c: char| c.is_ascii_digit()).ok_or(ParseError::Empty)?;
+        let last = input.rfind(|c: char| c.is_ascii_digit()).unwrap() + 1;
+        // ".5" 这样以分隔符开头的小数
+        let start = match input[..first].chars().next_back() {
+            Some(c @ ('.' | ',')) => first - c.len_utf8(),
+            _ => first,
+        };
+
+        // "12,-" 表示没有辅币
+        let mut rest = &input[last..];
+        let mut dash = None;
+        for separator in ['.', ','] {
+            if let Some(after) = rest.strip_prefix(separator) {
+                if let Some(after) = after.strip_prefix(['-', '\u{2013}', '\u{2014}']) {
+                    dash = Some(separator);
+                    rest = after;
+                }
+            }
+        }
+
+        // 数量级后缀须紧跟数字，且后面不能再接字母，以免把 "kr" 当成千
+        let mut magnitude = 0;
+        for (suffix, exp) in MAGNITUDES {
+            if let Some(after) = rest.strip_prefix(suffix) {
+                if !after.starts_with(char::is_alphabetic) {
+                    magnitude = *exp;
+                    rest = after;
+                    break;
+                }
+            }
+        }
+
+        let mut negative = false;
+        let mut parens = (0, 0);
+        let prefix = strip_signs(input, &input[..start], &mut negative, &mut parens)?;
+        let suffix = strip_signs(input, rest, &mut negative, &mut parens)?;
+        if parens.0 != parens.1 {
+            let position = input.find(['(', ')']).unwrap();
+            return Err(ParseError::UnexpectedCharacter { position, found: input[position..].chars().next().unwrap() });
+        }
+        // 会计格式用括号表示负数
+        negative |= parens.0 > 0;
+
+        let symbol = match (prefix.is_empty(), suffix.is_empty()) {
+            (_, true) => prefix,
+            (true, false) => suffix,
+            (false, false) => {
+                let position = offset(input, suffix);
+                return Err(ParseError::UnexpectedCharacter { position, found: suffix.chars().next().unwrap() });
+            }
+        };
+        let currencies = if symbol.is_empty() { Vec::new() } else { self.candidates(symbol) };
+        if currencies.is_empty() && !symbol.is_empty() {
+            return Err(ParseError::UnknownCurrency(symbol.to_string()));
+        }
+
+        let (amount, alternative, mut confidence) = read_number(&input[start..last], start, dash, &currencies)?;
+        let amount = to_rational(amount, magnitude, negative)?;
+        let alternative = alternative.map(|a| to_rational(a, magnitude, negative)).transpose()?;
+        if currencies.len() > 1 {
+            confidence = Confidence::Ambiguous;
+        }
+        Ok(LenientAmount {
+            amount,
+            alternative,
+            currencies,
+            confidence,
+        })
+    }
+
+    // 按代码、别名、符号、带国家前缀的符号、名称的顺序查找货币
+    fn candidates(&self, symbol: &str) -> Vec<Arc<Currency>> {
+        if let Some(currency) = self.get_currency_by_code(symbol).or_else(|| self.get_currency_by_alias(symbol)) {
+            return vec![Arc::clone(currency)];
+        }
+        let mut found = self.currencies_by_grapheme(symbol).to_vec();
+        if found.is_empty() {
+            if let Some(i) = symbol.find(|c: char| !c.is_ascii_alphabetic()).filter(|i| *i > 0) {
+                let prefix = symbol[..i].to_ascii_uppercase();
+                found = self
+                    .currencies_by_grapheme(&symbol[i..])
+                    .iter()
+                    .filter(|c| c.code().starts_with(&prefix) || c.countries().any(|country| country == prefix))
+                    .cloned()
+                    .collect();
+            }
+        }
+        if found.is_empty() {
+            let name = symbol.to_lowercase();
+            let singular = name.strip_suffix('s').unwrap_or(&name);
+            let names = |c: &&Arc<Currency>| {
+                let n = c.name().to_lowercase();
+                n == name || n == singular
+            };
+            found = self.active().filter(names).cloned().collect();
+            // 单个词可以只是名称的一部分，如 "dollars"
+            if found.is_empty() && !singular.contains(' ') {
+                found = self
+                    .active()
+                    .filter(|c| c.name().to_lowercase().split(' ').any(|word| word == singular))
+                    .cloned()
+                    .collect();
+            }
+        }
+        found.sort_by(|a, b| a.code().cmp(b.code()));
+        found
+    }
+}
+
+// 去掉两端的空白、正负号和括号，返回中间的货币部分；parens 记录左右括号的个数
+fn strip_signs<'a>(
+    input: &str,
+    mut text: &'a str,
+    negative: &mut bool,
+    parens: &mut (usize, usize),
+) -> Result<&'a str, ParseError> {
+    let is_sign = |c: char| matches!(c, '(' | ')' | '+') || MINUS.contains(&c);
+    loop {
+        text = text.trim();
+        let (rest, position, c) = match (text.chars().next(), text.chars().next_back()) {
+            (Some(c), _) if is_sign(c) => (&text[c.len_utf8()..], offset(input, text), c),
+            (_, Some(c)) if is_sign(c) => {
+                let end = text.len() - c.len_utf8();
+                (&text[..end], offset(input, text) + end, c)
+            }
+            _ => return Ok(text),
+        };
+        match c {
+            '(' => parens.0 += 1,
+            ')' => parens.1 += 1,
+            '+' => {}
+            _ if *negative => return Err(ParseError::UnexpectedCharacter { position, found: c }),
+            _ => *negative = true,
+        }
+        text = rest;
+    }
+}
+
+// (mantissa, scale)
+type Decimal = (i128, u32);
+
+// 推断分隔符，返回最可能的读法、另一种读法和置信度
+fn read_number(
+    number: &str,
+    start: usize,
+    dash: Option<char>,
+    currencies: &[Arc<Currency>],
+) -> Result<(Decimal, Option<Decimal>, Confidence), ParseError> {
+    let mut groups = Vec::new();
+    let mut separators = Vec::new();
+    let mut group_start = 0;
+    for (i, c) in number.char_indices() {
+        if c.is_ascii_digit() {
+            continue;
+        }
+        if !(c == '.' || c == ',' || GROUPING_ONLY.contains(&c)) || (i == group_start && i > 0) {
+            return Err(ParseError::UnexpectedCharacter { position: start + i, found: c });
+        }
+        groups.push(&number[group_start..i]);
+        separators.push(c);
+        group_start = i + c.len_utf8();
+    }
+    groups.push(&number[group_start..]);
+
+    // 两种读法：最后一个分隔符是小数点，或者全部都是分组符
+    let n = separators.len();
+    let as_decimal = n > 0
+        && dash.is_none()
+        && matches!(separators[n - 1], '.' | ',')
+        && !separators[..n - 1].contains(&separators[n - 1])
+        && (n == 1 || valid_grouping(&groups[..n], &separators[..n - 1]));
+    let as_grouping = valid_grouping(&groups, &separators) && dash.is_none_or(|d| !separators.contains(&d));
+
+    let decimal = |groups: &[&str]| digits(&groups[..n], groups[n].trim_end_matches('0'));
+    let grouped = |groups: &[&str]| digits(groups, "");
+    match (as_decimal, as_grouping) {
+        (false, false) => Err(ParseError::InvalidGrouping),
+        (true, false) => Ok((decimal(&groups)?, None, Confidence::Certain)),
+        (false, true) => Ok((grouped(&groups)?, None, Confidence::Certain)),
+        (true, true) => {
+            // 由货币的格式决定："12,345" 对两位小数的货币只能是分组
+            let separator = separators[0].to_string();
+            if !currencies.is_empty() && currencies.iter().all(|c| c.fraction < 3) {
+                Ok((grouped(&groups)?, None, Confidence::Likely))
+            } else if !currencies.is_empty() && currencies.iter().all(|c| c.decimal() == separator) {
+                Ok((decimal(&groups)?, None, Confidence::Likely))
+            } else {
+                Ok((grouped(&groups)?, Some(decimal(&groups)?), Confidence::Ambiguous))
+            }
+        }
+    }
+}
+
+// 分组符必须一致，首组不以 0 开头，按三位一组或印度的 lakh/crore 分组
+fn valid_grouping(groups: &[&str], separators: &[char]) -> bool {
+    let Some(first) = separators.first() else {
+        return true;
+    };
+    let sizes: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+    separators.iter().all(|s| s == first)
+        && !groups[0].starts_with('0')
+        && [Grouping::THOUSANDS, Grouping::INDIAN].iter().any(|g| g.accepts(&sizes))
+}
+
+fn digits(int_groups: &[&str], fraction: &str) -> Result<Decimal, ParseError> {
+    let mut mantissa: i128 = 0;
+    for c in int_groups.iter().flat_map(|g| g.chars()).chain(fraction.chars()) {
+        mantissa = mantissa
+            .checked_mul(10)
+            .and_then(|m| m.checked_add(c.to_digit(10).unwrap() as i128))
+            .ok_or(ParseError::Overflow)?;
+    }
+    Ok((mantissa, fraction.len() as u32))
+}
+
+fn to_rational((mantissa, scale): Decimal, magnitude: u32, negative: bool) -> Result<Rational, ParseError> {
+    let mantissa = if negative { -mantissa } else { mantissa };
+    let value = if scale >= magnitude {
+        Rational::from_decimal(mantissa, scale - magnitude)
+    } else {
+        rounding::pow10(magnitude - scale)
+            .and_then(|p| mantissa.checked_mul(p).ok_or(MoneyError::Overflow))
+            .and_then(|numer| Rational::new(numer, 1))
+    };
+    value.map_err(|_| ParseError::Overflow)
+}
+
+// text 在 input 中的字节位置；text 必须是 input 的切片
+fn offset(input: &str, text: &str) -> usize {
+    text.as_ptr() as usize - input.as_ptr() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> LenientAmount {
+        Currencies::default().parse_lenient(input).unwrap()
+    }
+
+    fn codes(parsed: &LenientAmount) -> Vec<&str> {
+        parsed.currencies.iter().map(|c| c.code()).collect()
+    }
+
+    #[test]
+    fn test_separators() {
+        let r = |s: &str| s.parse::<Rational>().unwrap();
+        assert_eq!(parse("1234").amount, r("1234"));
+        assert_eq!(parse("1,234.56").amount, r("1234.56"));
+        assert_eq!(parse("1.234.567,8").amount, r("1234567.8"));
+        assert_eq!(parse("1 234 567,89").amount, r("1234567.89"));
+        assert_eq!(parse("1'234.5").amount, r("1234.5"));
+        assert_eq!(parse("₹1,23,45,678.90").amount, r("12345678.9"));
+        assert_eq!(parse("12,5").amount, r("12.5"));
+        assert_eq!(parse("0,500").amount, r("0.5"));
+        assert_eq!(parse(".75").amount, r("0.75"));
+        assert_eq!(parse("1234.5678").amount, r("1234.5678"));
+        assert_eq!(parse("1.5k").amount, r("1500"));
+        assert_eq!(parse("2bn").amount, r("2000000000"));
+        assert_eq!(parse("1.234,-").amount, r("1234"));
+        assert_eq!(parse("12,345").confidence, Confidence::Ambiguous);
+        assert_eq!(parse("1,234.00").confidence, Confidence::Certain);
+        // 欧元只有两位小数，三位数字只能是分组
+        let eur = parse("12.345 EUR");
+        assert_eq!((eur.amount, eur.alternative, eur.confidence), (r("12345"), None, Confidence::Likely));
+        let kwd = parse("KWD 12.345");
+        assert_eq!((kwd.amount, kwd.confidence), (r("12.345"), Confidence::Likely));
+    }
+    #[test]
+    fn test_signs() {
+        let minus_twelve = Rational::from_integer(-12);
+        for input in ["-12", "−12", "(12)", "$ (12)", "(USD 12)", "12-", "-$12", "$-12", "12 € -"] {
+            assert_eq!(parse(input).amount, minus_twelve, "{}", input);
+        }
+        assert_eq!(parse("+12").amount, Rational::from_integer(12));
+    }
+    #[test]
+    fn test_currencies() {
+        assert_eq!(codes(&parse("US$12")), ["USD"]);
+        assert_eq!(codes(&parse("HK$ 12")), ["HKD"]);
+        assert_eq!(codes(&parse("A$12")), ["ARS", "AUD"]);
+        assert_eq!(codes(&parse("12 usd")), ["USD"]);
+        assert_eq!(codes(&parse("12 RMB")), ["CNY"]);
+        let pounds = parse("£12");
+        assert!(codes(&pounds).contains(&"GBP") && codes(&pounds).contains(&"EGP"));
+        assert_eq!(pounds.confidence, Confidence::Ambiguous);
+        assert_eq!(codes(&parse("12 euros")), ["EUR"]);
+        assert_eq!(codes(&parse("12 US dollars")), ["USD"]);
+        assert_eq!(codes(&parse("12 kr")), ["DKK", "ISK", "NOK", "SEK"]);
+        assert_eq!(codes(&parse("12 DEM")), ["DEM"]);
+        assert!(codes(&parse("12 dollars")).contains(&"CAD"));
+        assert!(parse("12").currencies.is_empty());
+    }
+    #[test]
+    fn test_lenient_errors() {
+        let currencies = Currencies::default();
+        assert_eq!(currencies.parse_lenient("USD"), Err(ParseError::Empty));
+        assert_eq!(currencies.parse_lenient("12 zorkmids"), Err(ParseError::UnknownCurrency("zorkmids".to_string())));
+        assert_eq!(currencies.parse_lenient("1,2345,678"), Err(ParseError::InvalidGrouping));
+        assert_eq!(currencies.parse_lenient("1,234,56,789"), Err(ParseError::InvalidGrouping));
+        assert_eq!(currencies.parse_lenient("1,,2"), Err(ParseError::UnexpectedCharacter { position: 2, found: ',' }));
+        assert_eq!(currencies.parse_lenient("1x2"), Err(ParseError::UnexpectedCharacter { position: 1, found: 'x' }));
+        assert_eq!(currencies.parse_lenient("(12"), Err(ParseError::UnexpectedCharacter { position: 0, found: '(' }));
+        assert_eq!(currencies.parse_lenient("--12"), Err(ParseError::UnexpectedCharacter { position: 1, found: '-' }));
+        assert_eq!(currencies.parse_lenient("$12 USD"), Err(ParseError::UnexpectedCharacter { position: 4, found: 'U' }));
+    }
+}
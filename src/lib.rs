@@ -1,63 +1,293 @@
+//! Basic and precise money operations such as rounding, splitting and allocating.
+//!
+//! Amounts are stored as an integer number of minor units (cents for `USD`,
+//! yen for `JPY`, ...) together with the [`Currency`] they are expressed in.
+//!
+//! ```
+//! use rust_money::prelude::*;
+//!
+//! let price = Money::new(1999, rust_money::USD);
+//! let total = price.add(&price).unwrap();
+//! assert_eq!(total.to_string(), "USD $39.98");
+//! ```
+
+mod allocation;
+mod code;
 mod constants;
 mod currency;
+mod date;
 mod formatter;
+mod lenient;
+mod loader;
+mod locale;
+mod ops;
+mod rational;
+mod registry;
+mod rounding;
+pub mod serde;
+pub mod typed;
 
 use std::cmp::Ordering;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::error::Error;
 use std::fmt;
-use crate::currency::Currency;
-use crate::formatter::Formatter;
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub use crate::allocation::{Allocation, AllocationStrategy, Share};
+pub use crate::constants::*;
+use crate::currency::Code;
+pub use crate::currency::{Currencies, Currency};
+pub use crate::date::Date;
+pub use crate::formatter::{Digits, Formatter, Grouping, ParseError};
+pub use crate::lenient::{Confidence, LenientAmount};
+pub use crate::loader::{CurrencyDefinition, LoadError, MergePolicy};
+pub use crate::locale::SymbolStyle;
+pub use crate::rational::Rational;
+pub use crate::registry::ScopedRegistry;
+pub use crate::rounding::RoundingMode;
 
+/// Convenience re-exports of the types needed by most users.
+///
+/// ```
+/// use rust_money::prelude::*;
+///
+/// let m = Money::new(100, "GBP");
+/// assert_eq!(*m.currency(), Currency::from_code("gbp").unwrap());
+/// ```
+pub mod prelude {
+    pub use crate::{Allocation, AllocationStrategy, Currencies, Currency, CurrencyCode, Money, MoneyError, Rational, RoundingMode, Share};
+}
 
-#[derive(Debug, Clone, PartialEq,Serialize)]
-struct Money {
+/// An amount of money in the minor units of a [`Currency`].
+///
+/// `Money` is `Copy`: it keeps only the code and number of decimal places
+/// of its currency, so it is no larger than two words. The amount therefore
+/// always keeps its meaning, while the symbol and format are looked up in
+/// the registry when needed; see [`Money::currency`].
+///
+/// See the [`serde`] module for its serialized form.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Money {
     amount: i64,
-    currency: Currency,
+    currency: Code,
+}
+
+/// The result of a lossy operation: the rounded money and the part that
+/// was rounded away.
+///
+/// `residue` is the exact result minus `money`, in minor units, so booking
+/// it alongside `money` accounts for every fraction of a cent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rounded {
+    pub money: Money,
+    pub residue: Rational,
 }
 
 impl fmt::Display for Money {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let c = &self.currency;
+        let c = self.currency();
         let formatted_amount = c.formatter().format(self.amount);
         write!(f, "{} {}", c.code, formatted_amount)
     }
 }
 
+/// Parses the `Display` form, a currency code followed by the amount as
+/// the currency formats it. Non-zero digits beyond the currency's fraction
+/// are rejected; see [`Money::parse_with`] to round them instead.
+///
+/// ```
+/// use rust_money::{Money, ParseError};
+///
+/// assert_eq!("USD $1,234.56".parse(), Ok(Money::new(123456, "USD")));
+/// assert_eq!("BRL -R$1.234,56".parse(), Ok(Money::new(-123456, "BRL")));
+/// assert_eq!("DKK kr 1.234,56".parse(), Ok(Money::new(123456, "DKK")));
+/// assert_eq!("USD 1234.5".parse(), Ok(Money::new(123450, "USD")));
+/// assert_eq!("XYZ 1".parse::<Money>(), Err(ParseError::UnknownCurrency("XYZ".to_string())));
+/// ```
+impl FromStr for Money {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Money::parse_with(s, RoundingMode::Unnecessary)
+    }
+}
+
 impl Money {
-    fn new(amount: i64, code: &str) -> Self {
-        let mut currency = Currency::new(code);
-        currency.get();
+    /// Creates a new `Money` from an amount in minor units and a currency code.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let m = Money::new(100, "GBP");
+    /// assert_eq!(m.amount(), 100);
+    /// assert_eq!(m.to_string(), "GBP £1.00");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `code` is neither in the currency registry nor a built-in
+    /// code. Intended for [`CurrencyCode`] values and the code constants of
+    /// this crate, such as [`USD`], which fall back to the built-in
+    /// definition if the registry lacks them, so they never panic; use
+    /// [`Money::try_new`] for codes that come from input.
+    pub fn new(amount: i64, code: impl AsRef<str>) -> Self {
+        let currency = Currency::resolve_or_builtin(code.as_ref()).unwrap_or_else(|err| panic!("{}", err));
         Money {
             amount,
-            currency,
+            currency: currency.key(),
         }
     }
 
-    fn new_from_float(amount: f64, code: &str) -> Self {
-        let mut currency = Currency::new(code); // 假设货币的小数位数为2，可以根据实际情况调整
-        currency.get();
-        let factor = 10i64.pow(currency.fraction as u32);
-        let amount = (amount * factor as f64).floor() as i64;
-        Money {
+    /// Creates a new `Money`, failing if `code` is not in the currency
+    /// registry.
+    ///
+    /// ```
+    /// use rust_money::{Money, MoneyError};
+    ///
+    /// assert_eq!(Money::try_new(100, "gbp").unwrap().currency().code(), "GBP");
+    /// assert_eq!(Money::try_new(100, "XYZ"), Err(MoneyError::UnknownCurrency("XYZ".to_string())));
+    /// ```
+    pub fn try_new(amount: i64, code: impl AsRef<str>) -> Result<Self, MoneyError> {
+        Ok(Money {
             amount,
-            currency,
-        }
+            currency: Currency::resolve(code.as_ref())?.key(),
+        })
+    }
+
+    /// Creates a new `Money` from an amount in major units, flooring any
+    /// digits beyond the currency's fraction.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// assert_eq!(Money::new_from_float(12.345, "USD").amount(), 1234);
+    /// assert_eq!(Money::new_from_float(0.29, "USD").amount(), 29);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `code` is neither in the currency registry nor a built-in
+    /// code, like [`Money::new`], or if `amount` is not finite or does not
+    /// fit in minor units; see [`Money::new_from_float_with`].
+    pub fn new_from_float(amount: f64, code: &str) -> Self {
+        Currency::resolve_or_builtin(code)
+            .and_then(|currency| Self::from_float_in(amount, &currency, RoundingMode::Floor))
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 
-    fn currency(&self) -> &Currency {
-        &self.currency
+    /// Creates a new `Money` from an amount in major units, rounding any
+    /// digits beyond the currency's fraction with `mode`.
+    ///
+    /// The float is read as its shortest decimal representation, so no
+    /// binary rounding error leaks into the result.
+    ///
+    /// ```
+    /// use rust_money::{Money, RoundingMode};
+    ///
+    /// let m = Money::new_from_float_with(2.675, "USD", RoundingMode::HalfEven).unwrap();
+    /// assert_eq!(m.amount(), 268);
+    /// ```
+    ///
+    /// NaN and infinite amounts are rejected with [`MoneyError::NotFinite`].
+    pub fn new_from_float_with(amount: f64, code: &str, mode: RoundingMode) -> Result<Self, MoneyError> {
+        let currency = Currency::resolve(code)?;
+        Self::from_float_in(amount, &currency, mode)
     }
 
-    fn amount(&self) -> i64 {
+    fn from_float_in(amount: f64, currency: &Currency, mode: RoundingMode) -> Result<Self, MoneyError> {
+        let (mantissa, scale) = rounding::decimal_from_f64(amount)?;
+        let amount = rounding::to_i64(rounding::rescale(mantissa, scale, currency.fraction as u32, mode)?)?;
+        Ok(Money {
+            amount,
+            currency: currency.key(),
+        })
+    }
+
+    /// Parses the `Display` form like [`FromStr`], rounding digits beyond
+    /// the currency's fraction with `mode`.
+    ///
+    /// ```
+    /// use rust_money::{Money, RoundingMode};
+    ///
+    /// let m = Money::parse_with("USD $10.005", RoundingMode::HalfUp).unwrap();
+    /// assert_eq!(m, Money::new(1001, "USD"));
+    /// ```
+    pub fn parse_with(s: &str, mode: RoundingMode) -> Result<Self, ParseError> {
+        let trimmed = s.trim_start();
+        let (code, amount) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+        let currency = Currency::resolve(code).map_err(|_| ParseError::UnknownCurrency(code.to_string()))?;
+        // 错误位置按整个输入计算
+        let offset = s.len() - amount.len();
+        let amount = currency.formatter().parse_with(amount, mode).map_err(|err| match err {
+            ParseError::UnexpectedCharacter { position, found } => {
+                ParseError::UnexpectedCharacter { position: position + offset, found }
+            }
+            err => err,
+        })?;
+        Ok(Money {
+            amount,
+            currency: currency.key(),
+        })
+    }
+
+    /// Formats the amount as written in `locale`, e.g. `"de-CH"`, using the
+    /// vendored CLDR data. Falls back to the currency's own format, as in
+    /// `Display` but without the code, when the locale is unknown.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let m = Money::new(123450, "CHF");
+    /// assert_eq!(m.format_locale("de-CH"), "CHF\u{a0}1’234.50");
+    /// assert_eq!(m.format_locale("fr-CH"), "1\u{202f}234,50\u{a0}CHF");
+    /// assert_eq!(m.format_locale("und"), "1,234.50 CHF");
+    /// ```
+    pub fn format_locale(&self, locale: &str) -> String {
+        self.format_locale_with(locale, SymbolStyle::Standard)
+    }
+
+    /// Like [`Money::format_locale`], with a choice of symbol.
+    pub fn format_locale_with(&self, locale: &str, style: SymbolStyle) -> String {
+        let currency = self.currency();
+        Formatter::for_locale(&currency, locale, style)
+            .unwrap_or_else(|| currency.formatter())
+            .format(self.amount)
+    }
+
+    /// The definition of the currency in the registry.
+    ///
+    /// If the registry visible to the current thread no longer has a
+    /// definition with the same number of decimal places, e.g. because the
+    /// currency was unregistered, redefined, or registered only in a
+    /// [scoped](Currencies::scoped) registry that has ended or belongs to
+    /// another thread, the built-in definition is used, or else a plain one
+    /// with the code as symbol.
+    ///
+    /// ```
+    /// use rust_money::{Currencies, Currency, Money};
+    ///
+    /// let m = {
+    ///     let _scope = Currencies::scoped(Currencies::default());
+    ///     Currencies::register(Currency::new_all("XTS", "¤", "963", "1 $", ".", ",", 2));
+    ///     Money::new(150, "XTS")
+    /// };
+    /// assert_eq!(m.to_string(), "XTS 1.50 XTS");
+    /// ```
+    pub fn currency(&self) -> Arc<Currency> {
+        self.currency.currency()
+    }
+
+    /// Returns the amount in minor units.
+    pub fn amount(&self) -> i64 {
         self.amount
     }
 
-    fn same_currency(&self, other: &Money) -> bool {
-        self.currency.eq(&other.currency)
+    pub fn same_currency(&self, other: &Money) -> bool {
+        self.currency == other.currency
     }
 
-    fn assert_same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+    /// Returns `MoneyError::CurrencyMismatch` unless both values share a currency.
+    pub fn assert_same_currency(&self, other: &Money) -> Result<(), MoneyError> {
         if !self.same_currency(other) {
             Err(MoneyError::CurrencyMismatch)
         } else {
@@ -65,141 +295,641 @@ impl Money {
         }
     }
 
-    fn compare(&self, other: &Money) -> Ordering {
+    /// Compares the amounts, ignoring the currencies.
+    pub fn compare(&self, other: &Money) -> Ordering {
         self.amount.cmp(&other.amount)
     }
 
-    fn equals(&self, other: &Money) -> Result<bool, MoneyError> {
+    /// ```
+    /// use rust_money::{Money, MoneyError};
+    ///
+    /// let m2 = Money::new(200, "GBP");
+    /// let m3 = Money::new(200, "EUR");
+    /// assert_eq!(m2.equals(&m2), Ok(true));
+    /// assert_eq!(m2.equals(&m3), Err(MoneyError::CurrencyMismatch));
+    /// ```
+    pub fn equals(&self, other: &Money) -> Result<bool, MoneyError> {
         self.assert_same_currency(other)?;
         Ok(self.compare(other) == Ordering::Equal)
     }
 
-    fn greater_than(&self, other: &Money) -> Result<bool, MoneyError> {
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let m1 = Money::new(100, "GBP");
+    /// let m2 = Money::new(200, "GBP");
+    /// assert_eq!(m1.greater_than(&m2), Ok(false));
+    /// ```
+    pub fn greater_than(&self, other: &Money) -> Result<bool, MoneyError> {
         self.assert_same_currency(other)?;
         Ok(self.compare(other) == Ordering::Greater)
     }
 
-    fn greater_than_or_equal(&self, other: &Money) -> Result<bool, MoneyError> {
+    pub fn greater_than_or_equal(&self, other: &Money) -> Result<bool, MoneyError> {
         self.assert_same_currency(other)?;
         Ok(self.compare(other) != Ordering::Less)
     }
 
-    fn less_than(&self, other: &Money) -> Result<bool, MoneyError> {
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let m1 = Money::new(100, "GBP");
+    /// let m2 = Money::new(200, "GBP");
+    /// assert_eq!(m1.less_than(&m2), Ok(true));
+    /// ```
+    pub fn less_than(&self, other: &Money) -> Result<bool, MoneyError> {
         self.assert_same_currency(other)?;
         Ok(self.compare(other) == Ordering::Less)
     }
 
-    fn less_than_or_equal(&self, other: &Money) -> Result<bool, MoneyError> {
+    pub fn less_than_or_equal(&self, other: &Money) -> Result<bool, MoneyError> {
         self.assert_same_currency(other)?;
         Ok(self.compare(other) != Ordering::Greater)
     }
 
-    fn is_zero(&self) -> bool {
+    pub fn is_zero(&self) -> bool {
         self.amount == 0
     }
     // 正值
-    fn is_positive(&self) -> bool {
+    pub fn is_positive(&self) -> bool {
         self.amount > 0
     }
     // 负值
-    fn is_negative(&self) -> bool {
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// assert!(!Money::new(100, "GBP").is_negative());
+    /// assert!(Money::new(-100, "GBP").is_negative());
+    /// ```
+    pub fn is_negative(&self) -> bool {
         self.amount < 0
     }
 
-    fn absolute(&self) -> Self {
-        Money {
-            amount: self.amount.abs(),
-            currency: self.currency.clone(),
-        }
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let m1 = Money::new(-100, "cny");
+    /// assert_eq!(m1.absolute(), Money::new(100, "CNY"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the amount is `i64::MIN`; see [`Money::checked_abs`].
+    pub fn absolute(&self) -> Self {
+        self.checked_abs().expect("attempt to take the absolute value of i64::MIN")
     }
 
-    fn negative(&self) -> Self {
-        Money {
-            amount: -self.amount,
-            currency: self.currency.clone(),
-        }
+    /// # Panics
+    ///
+    /// Panics if the amount is `i64::MIN`; see [`Money::checked_neg`].
+    pub fn negative(&self) -> Self {
+        self.checked_neg().expect("attempt to negate i64::MIN")
+    }
+
+    /// Adds two values of the same currency.
+    ///
+    /// ```
+    /// use rust_money::{Money, MoneyError};
+    ///
+    /// let m1 = Money::new(100, "GBP");
+    /// let m2 = Money::new(200, "GBP");
+    /// assert_eq!(m1.add(&m2).unwrap(), Money::new(300, "GBP"));
+    /// assert_eq!(m1.add(&Money::new(1, "EUR")), Err(MoneyError::CurrencyMismatch));
+    /// assert_eq!(m1.add(&Money::new(i64::MAX, "GBP")), Err(MoneyError::Overflow));
+    /// ```
+    pub fn add(&self, other: &Money) -> Result<Self, MoneyError> {
+        self.checked_add(other)
+    }
+
+    /// Subtracts `other`, failing on a currency mismatch or overflow.
+    pub fn subtract(&self, other: &Money) -> Result<Self, MoneyError> {
+        self.checked_sub(other)
+    }
+
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// assert_eq!(Money::new(100, "GBP").multiply(2), Money::new(200, "GBP"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the product overflows; see [`Money::checked_mul`].
+    pub fn multiply(&self, multiplier: i64) -> Self {
+        self.checked_mul(multiplier).expect("attempt to multiply with overflow")
     }
 
-    fn add(&self, other: &Money) -> Result<Self, MoneyError> {
+    pub fn checked_add(&self, other: &Money) -> Result<Self, MoneyError> {
         self.assert_same_currency(other)?;
-        Ok(Money {
-            amount: self.amount + other.amount,
-            currency: self.currency.clone(),
-        })
+        self.amount.checked_add(other.amount).map(|a| self.with_amount(a)).ok_or(MoneyError::Overflow)
     }
 
-    fn subtract(&self, other: &Money) -> Result<Self, MoneyError> {
+    pub fn checked_sub(&self, other: &Money) -> Result<Self, MoneyError> {
         self.assert_same_currency(other)?;
-        Ok(Money {
-            amount: self.amount - other.amount,
-            currency: self.currency.clone(),
-        })
+        self.amount.checked_sub(other.amount).map(|a| self.with_amount(a)).ok_or(MoneyError::Overflow)
     }
 
-    fn multiply(&self, multiplier: i64) -> Self {
-        Money {
-            amount: self.amount * multiplier,
-            currency: self.currency.clone(),
+    /// ```
+    /// use rust_money::{Money, MoneyError};
+    ///
+    /// assert_eq!(Money::new(i64::MAX, "USD").checked_mul(2), Err(MoneyError::Overflow));
+    /// ```
+    pub fn checked_mul(&self, multiplier: i64) -> Result<Self, MoneyError> {
+        self.amount.checked_mul(multiplier).map(|a| self.with_amount(a)).ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_neg(&self) -> Result<Self, MoneyError> {
+        self.amount.checked_neg().map(|a| self.with_amount(a)).ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_abs(&self) -> Result<Self, MoneyError> {
+        self.amount.checked_abs().map(|a| self.with_amount(a)).ok_or(MoneyError::Overflow)
+    }
+
+    /// Divides the amount by `divisor`, truncating toward zero.
+    ///
+    /// ```
+    /// use rust_money::{Money, MoneyError};
+    ///
+    /// assert_eq!(Money::new(700, "USD").checked_div(3), Ok(Money::new(233, "USD")));
+    /// assert_eq!(Money::new(700, "USD").checked_div(0), Err(MoneyError::DivisionByZero));
+    /// ```
+    pub fn checked_div(&self, divisor: i64) -> Result<Self, MoneyError> {
+        if divisor == 0 {
+            return Err(MoneyError::DivisionByZero);
         }
+        self.amount.checked_div(divisor).map(|a| self.with_amount(a)).ok_or(MoneyError::Overflow)
     }
 
-    fn round(&self) -> Self {
-        let factor = 10i64.pow(self.currency.fraction as u32);
-        let rounded_amount = ((self.amount as f64 / factor as f64).round() * factor as f64) as i64;
-        Money {
-            amount: rounded_amount,
-            currency: self.currency.clone(),
+    /// The remainder left by [`Money::checked_div`].
+    pub fn checked_rem(&self, divisor: i64) -> Result<Self, MoneyError> {
+        if divisor == 0 {
+            return Err(MoneyError::DivisionByZero);
         }
+        self.amount.checked_rem(divisor).map(|a| self.with_amount(a)).ok_or(MoneyError::Overflow)
     }
 
-    fn split(&self, parts: usize) -> Result<Vec<Money>, MoneyError> {
-        if parts <= 0 {
-            return Err(MoneyError::InvalidSplit);
+    /// Adds `other`, clamping the result to the `i64` range.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let max = Money::new(i64::MAX, "USD");
+    /// assert_eq!(max.saturating_add(&Money::new(1, "USD")).unwrap(), max);
+    /// ```
+    pub fn saturating_add(&self, other: &Money) -> Result<Self, MoneyError> {
+        self.assert_same_currency(other)?;
+        Ok(self.with_amount(self.amount.saturating_add(other.amount)))
+    }
+
+    pub fn saturating_sub(&self, other: &Money) -> Result<Self, MoneyError> {
+        self.assert_same_currency(other)?;
+        Ok(self.with_amount(self.amount.saturating_sub(other.amount)))
+    }
+
+    pub fn saturating_mul(&self, multiplier: i64) -> Self {
+        self.with_amount(self.amount.saturating_mul(multiplier))
+    }
+
+    pub fn saturating_neg(&self) -> Self {
+        self.with_amount(self.amount.saturating_neg())
+    }
+
+    pub fn saturating_abs(&self) -> Self {
+        self.with_amount(self.amount.saturating_abs())
+    }
+
+    /// Adds `other` with wrapping arithmetic, also reporting whether an
+    /// overflow occurred.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let (sum, overflowed) = Money::new(i64::MAX, "USD")
+    ///     .overflowing_add(&Money::new(1, "USD"))
+    ///     .unwrap();
+    /// assert_eq!(sum.amount(), i64::MIN);
+    /// assert!(overflowed);
+    /// ```
+    pub fn overflowing_add(&self, other: &Money) -> Result<(Self, bool), MoneyError> {
+        self.assert_same_currency(other)?;
+        let (amount, overflowed) = self.amount.overflowing_add(other.amount);
+        Ok((self.with_amount(amount), overflowed))
+    }
+
+    pub fn overflowing_sub(&self, other: &Money) -> Result<(Self, bool), MoneyError> {
+        self.assert_same_currency(other)?;
+        let (amount, overflowed) = self.amount.overflowing_sub(other.amount);
+        Ok((self.with_amount(amount), overflowed))
+    }
+
+    pub fn overflowing_mul(&self, multiplier: i64) -> (Self, bool) {
+        let (amount, overflowed) = self.amount.overflowing_mul(multiplier);
+        (self.with_amount(amount), overflowed)
+    }
+
+    pub fn overflowing_neg(&self) -> (Self, bool) {
+        let (amount, overflowed) = self.amount.overflowing_neg();
+        (self.with_amount(amount), overflowed)
+    }
+
+    pub fn overflowing_abs(&self) -> (Self, bool) {
+        let (amount, overflowed) = self.amount.overflowing_abs();
+        (self.with_amount(amount), overflowed)
+    }
+
+    /// Rounds the amount to a whole number of major units, with ties away
+    /// from zero.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// assert_eq!(Money::new(1250, "USD").round(), Money::new(1300, "USD"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rounded amount overflows; see [`Money::round_with`].
+    pub fn round(&self) -> Self {
+        self.round_with(RoundingMode::HalfUp).expect("attempt to round with overflow")
+    }
+
+    /// Rounds the amount to a whole number of major units using `mode`.
+    ///
+    /// ```
+    /// use rust_money::{Money, MoneyError, RoundingMode};
+    ///
+    /// let m = Money::new(1250, "USD");
+    /// assert_eq!(m.round_with(RoundingMode::HalfEven), Ok(Money::new(1200, "USD")));
+    /// assert_eq!(m.round_with(RoundingMode::Unnecessary), Err(MoneyError::RoundingNecessary));
+    /// ```
+    pub fn round_with(&self, mode: RoundingMode) -> Result<Self, MoneyError> {
+        let factor = rounding::pow10(self.currency.fraction())?;
+        let units = rounding::div_round(self.amount as i128, factor, mode)?;
+        let amount = units.checked_mul(factor).ok_or(MoneyError::Overflow)?;
+        Ok(self.with_amount(rounding::to_i64(amount)?))
+    }
+
+    /// Rounds the amount to a multiple of `increment` minor units.
+    ///
+    /// ```
+    /// use rust_money::{Money, RoundingMode};
+    ///
+    /// let m = Money::new(1238, "USD");
+    /// assert_eq!(m.round_to_increment(25, RoundingMode::HalfUp), Ok(Money::new(1250, "USD")));
+    /// assert_eq!(m.round_to_increment(25, RoundingMode::Floor), Ok(Money::new(1225, "USD")));
+    /// ```
+    pub fn round_to_increment(&self, increment: i64, mode: RoundingMode) -> Result<Self, MoneyError> {
+        self.round_to_offset_increment(increment, 0, mode)
+    }
+
+    /// Rounds to a "charm" price point: a whole number of major units plus
+    /// `ending` minor units, e.g. `99` for prices ending in `.99`.
+    ///
+    /// ```
+    /// use rust_money::{Money, RoundingMode};
+    ///
+    /// let m = Money::new(1240, "USD");
+    /// assert_eq!(m.round_to_price_point(99, RoundingMode::HalfUp), Ok(Money::new(1199, "USD")));
+    /// assert_eq!(m.round_to_price_point(95, RoundingMode::Ceiling), Ok(Money::new(1295, "USD")));
+    /// ```
+    pub fn round_to_price_point(&self, ending: i64, mode: RoundingMode) -> Result<Self, MoneyError> {
+        let unit = rounding::to_i64(rounding::pow10(self.currency.fraction())?)?;
+        if !(0..unit).contains(&ending) {
+            return Err(MoneyError::InvalidIncrement);
         }
-        // 计算每部分的金额和余数
-        let part_amount = self.amount / parts as i64;
-        let remainder = self.amount % parts as i64;
-        let mut result = vec![Money::new(part_amount, &self.currency.code); parts];
-        // 将余数加到相应的金额上
-        for i in 0..remainder.abs() as usize {
-            result[i].amount += remainder.signum();
+        self.round_to_offset_increment(unit, ending, mode)
+    }
+
+    /// Rounds to the currency's cash-rounding increment, with ties away from
+    /// zero. Currencies without cash rounding are returned unchanged.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// assert_eq!(Money::new(1013, "CHF").cash_rounded(), Money::new(1015, "CHF"));
+    /// assert_eq!(Money::new(1049, "SEK").cash_rounded(), Money::new(1000, "SEK"));
+    /// assert_eq!(Money::new(1013, "EUR").cash_rounded(), Money::new(1013, "EUR"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rounded amount overflows; see [`Money::cash_rounded_with`].
+    pub fn cash_rounded(&self) -> Self {
+        self.cash_rounded_with(RoundingMode::HalfUp).expect("attempt to round with overflow")
+    }
+
+    /// Rounds to the currency's cash-rounding increment using `mode`.
+    pub fn cash_rounded_with(&self, mode: RoundingMode) -> Result<Self, MoneyError> {
+        self.round_to_increment(self.currency().cash_rounding(), mode)
+    }
+
+    // 舍入到 offset + k * increment 形式的金额
+    fn round_to_offset_increment(&self, increment: i64, offset: i64, mode: RoundingMode) -> Result<Self, MoneyError> {
+        if increment <= 0 {
+            return Err(MoneyError::InvalidIncrement);
         }
+        let steps = rounding::div_round(self.amount as i128 - offset as i128, increment as i128, mode)?;
+        let amount = steps * increment as i128 + offset as i128;
+        Ok(self.with_amount(rounding::to_i64(amount)?))
+    }
 
-        Ok(result)
+    /// Divides the amount by `divisor`, rounding the quotient with `mode`.
+    ///
+    /// ```
+    /// use rust_money::{Money, RoundingMode};
+    ///
+    /// let m = Money::new(1000, "USD");
+    /// assert_eq!(m.divide(3, RoundingMode::Ceiling), Ok(Money::new(334, "USD")));
+    /// ```
+    pub fn divide(&self, divisor: i64, mode: RoundingMode) -> Result<Self, MoneyError> {
+        let amount = rounding::div_round(self.amount as i128, divisor as i128, mode)?;
+        Ok(self.with_amount(rounding::to_i64(amount)?))
     }
 
-    fn allocate(&self, ratios: &[u32]) -> Result<Vec<Money>, MoneyError> {
-        if ratios.is_empty() {
-            return Err(MoneyError::NoRatios);
+    /// Multiplies by an exact factor, rounding the product back to the
+    /// currency's minor units with `mode`.
+    ///
+    /// ```
+    /// use rust_money::{Money, Rational, RoundingMode};
+    ///
+    /// let net = Money::new(1999, "EUR");
+    /// let gross = net.multiply_exact("1.19".parse().unwrap(), RoundingMode::HalfEven).unwrap();
+    /// assert_eq!(gross.money, Money::new(2379, "EUR"));
+    /// // 1999 * 1.19 = 2378.81, so 0.19 minor units were rounded away.
+    /// assert_eq!(gross.residue, Rational::new(-19, 100).unwrap());
+    /// ```
+    pub fn multiply_exact(&self, factor: Rational, mode: RoundingMode) -> Result<Rounded, MoneyError> {
+        let numerator = (self.amount as i128).checked_mul(factor.numer()).ok_or(MoneyError::Overflow)?;
+        let rounded = rounding::div_round(numerator, factor.denom(), mode)?;
+        let money = self.with_amount(rounding::to_i64(rounded)?);
+        // 余数 = 精确值 - 舍入值 = (numerator - rounded * denom) / denom
+        let residue_numer = numerator - rounded * factor.denom();
+        Ok(Rounded {
+            money,
+            residue: Rational::new(residue_numer, factor.denom())?,
+        })
+    }
+
+    /// Divides by an exact divisor, rounding the quotient back to the
+    /// currency's minor units with `mode`.
+    ///
+    /// ```
+    /// use rust_money::{Money, Rational, RoundingMode};
+    ///
+    /// let share = Money::new(1000, "USD").divide_exact(Rational::from_integer(3), RoundingMode::HalfUp).unwrap();
+    /// assert_eq!(share.money, Money::new(333, "USD"));
+    /// assert_eq!(share.residue, Rational::new(1, 3).unwrap());
+    /// ```
+    pub fn divide_exact(&self, divisor: Rational, mode: RoundingMode) -> Result<Rounded, MoneyError> {
+        if divisor.is_zero() {
+            return Err(MoneyError::DivisionByZero);
         }
-        // 计算 ratios 数组的总和
-        let total: u32 = ratios.iter().sum();
-        if total == 0 {
-            return Ok(vec![Money::new(0, &self.currency.code); ratios.len()]);
+        self.multiply_exact(divisor.recip()?, mode)
+    }
+
+    /// Computes a percentage of the amount. The percentage is a decimal with
+    /// `scale` fractional digits, so `(725, 2)` means 7.25%.
+    ///
+    /// ```
+    /// use rust_money::{Money, RoundingMode};
+    ///
+    /// let net = Money::new(1999, "USD");
+    /// assert_eq!(net.percentage(725, 2, RoundingMode::HalfUp), Ok(Money::new(145, "USD")));
+    /// assert_eq!(net.percentage(19, 0, RoundingMode::HalfEven), Ok(Money::new(380, "USD")));
+    /// ```
+    pub fn percentage(&self, percent: i64, scale: u32, mode: RoundingMode) -> Result<Self, MoneyError> {
+        let divisor = rounding::pow10(scale)?.checked_mul(100).ok_or(MoneyError::Overflow)?;
+        let amount = rounding::mul_div_round(self.amount as i128, percent as i128, divisor, mode)?;
+        Ok(self.with_amount(rounding::to_i64(amount)?))
+    }
+
+    /// Converts to the currency `code` at an exchange rate given as a
+    /// decimal with `rate_scale` fractional digits, so `(10825, 4)` means
+    /// one unit of this currency buys 1.0825 units of `code`. Differences in
+    /// the currencies' fractions are taken into account.
+    ///
+    /// ```
+    /// use rust_money::{Money, RoundingMode};
+    ///
+    /// let eur = Money::new(1000, "EUR");
+    /// let jpy = eur.convert("JPY", 16215, 2, RoundingMode::HalfEven).unwrap();
+    /// assert_eq!(jpy, Money::new(1622, "JPY"));
+    /// ```
+    pub fn convert(&self, code: &str, rate: i64, rate_scale: u32, mode: RoundingMode) -> Result<Self, MoneyError> {
+        let currency = Currency::resolve(code)?;
+        let multiplier = rounding::pow10(currency.fraction as u32)?
+            .checked_mul(rate as i128)
+            .ok_or(MoneyError::Overflow)?;
+        let divisor = rounding::pow10(rate_scale + self.currency.fraction())?;
+        let amount = rounding::mul_div_round(self.amount as i128, multiplier, divisor, mode)?;
+        Ok(Money {
+            amount: rounding::to_i64(amount)?,
+            currency: currency.key(),
+        })
+    }
+
+    /// Splits the money into `parts` equal parts, handing any leftover minor
+    /// units to the first parts.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let parts = Money::new(200, "USD").split(3).unwrap();
+    /// let amounts: Vec<i64> = parts.iter().map(Money::amount).collect();
+    /// assert_eq!(amounts, [67, 67, 66]);
+    /// ```
+    pub fn split(&self, parts: usize) -> Result<Vec<Money>, MoneyError> {
+        self.split_with(parts, AllocationStrategy::ToFirst)
+    }
+
+    /// Splits the money into `parts` equal parts, handing any leftover minor
+    /// units out according to `strategy`.
+    pub fn split_with(&self, parts: usize, strategy: AllocationStrategy) -> Result<Vec<Money>, MoneyError> {
+        if parts == 0 {
+            return Err(MoneyError::InvalidSplit);
         }
+        self.allocate_weights(&vec![1; parts], strategy)
+    }
 
-        let mut result = Vec::with_capacity(ratios.len());
-        // 用于跟踪已分配的总金额
-        let mut total_allocated = 0;
-        for &ratio in ratios {
-            let amount = self.amount * ratio as i64 / total as i64;
-            result.push(Money::new(amount, &self.currency.code));
-            total_allocated += amount;
+    /// Allocates the money according to `ratios`, handing any leftover minor
+    /// units to the first parties.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let parts = Money::new(-100, "GBP").allocate(&[33, 33, 33]).unwrap();
+    /// let amounts: Vec<i64> = parts.iter().map(Money::amount).collect();
+    /// assert_eq!(amounts, [-34, -33, -33]);
+    /// ```
+    pub fn allocate(&self, ratios: &[u32]) -> Result<Vec<Money>, MoneyError> {
+        self.allocate_with(ratios, AllocationStrategy::ToFirst)
+    }
+
+    /// Allocates the money according to `ratios`, handing any leftover minor
+    /// units out according to `strategy`. The parts always sum to `self`.
+    ///
+    /// ```
+    /// use rust_money::{AllocationStrategy, Money};
+    ///
+    /// let parts = Money::new(100, "USD").allocate_with(&[1, 1, 5], AllocationStrategy::LargestRemainder).unwrap();
+    /// let amounts: Vec<i64> = parts.iter().map(Money::amount).collect();
+    /// assert_eq!(amounts, [14, 14, 72]);
+    /// ```
+    pub fn allocate_with(&self, ratios: &[u32], strategy: AllocationStrategy) -> Result<Vec<Money>, MoneyError> {
+        let weights: Vec<u128> = ratios.iter().map(|&r| r as u128).collect();
+        self.allocate_weights(&weights, strategy)
+    }
+
+    /// Allocates the money according to `u64` ratios. Intermediate products
+    /// are computed in 128 bits, so no ratio or amount can overflow.
+    ///
+    /// ```
+    /// use rust_money::{AllocationStrategy, Money};
+    ///
+    /// let parts = Money::new(i64::MAX, "USD").allocate_u64(&[u64::MAX, u64::MAX], AllocationStrategy::ToLast).unwrap();
+    /// assert_eq!(parts[0].amount(), i64::MAX / 2);
+    /// assert_eq!(parts[1].amount(), i64::MAX / 2 + 1);
+    /// ```
+    pub fn allocate_u64(&self, ratios: &[u64], strategy: AllocationStrategy) -> Result<Vec<Money>, MoneyError> {
+        let weights: Vec<u128> = ratios.iter().map(|&r| r as u128).collect();
+        self.allocate_weights(&weights, strategy)
+    }
+
+    /// Allocates the money according to percentages, which must be
+    /// non-negative and add up to exactly 100.
+    ///
+    /// ```
+    /// use rust_money::{AllocationStrategy, Money, MoneyError, Rational};
+    ///
+    /// let percentages: Vec<Rational> = ["12.5", "37.5", "50"].iter().map(|p| p.parse().unwrap()).collect();
+    /// let parts = Money::new(999, "EUR").allocate_percentages(&percentages, AllocationStrategy::LargestRemainder).unwrap();
+    /// let amounts: Vec<i64> = parts.iter().map(Money::amount).collect();
+    /// assert_eq!(amounts, [125, 375, 499]);
+    ///
+    /// let short = [Rational::from_integer(40), Rational::from_integer(50)];
+    /// assert_eq!(Money::new(999, "EUR").allocate_percentages(&short, AllocationStrategy::ToFirst), Err(MoneyError::InvalidRatios));
+    /// ```
+    pub fn allocate_percentages(&self, percentages: &[Rational], strategy: AllocationStrategy) -> Result<Vec<Money>, MoneyError> {
+        if percentages.iter().any(|p| p.numer() < 0) {
+            return Err(MoneyError::NegativeRatio);
         }
-        // 计算剩余金额（remainder），这是由于整数除法可能会产生的
-        // 将 remainder.signum() 加到相应的金额上
-        let remainder = self.amount - total_allocated;
-        for i in 0..remainder.abs() as usize {
-            result[i].amount += remainder.signum();
+        // 通分后用分子作为整数权重
+        let denom = percentages.iter().try_fold(1i128, |acc, p| allocation::lcm(acc, p.denom()))?;
+        let weights = percentages
+            .iter()
+            .map(|p| p.numer().checked_mul(denom / p.denom()).map(|w| w as u128).ok_or(MoneyError::Overflow))
+            .collect::<Result<Vec<u128>, MoneyError>>()?;
+        let total = weights.iter().try_fold(0u128, |sum, &w| sum.checked_add(w)).ok_or(MoneyError::Overflow)?;
+        if total != (denom as u128).checked_mul(100).ok_or(MoneyError::Overflow)? {
+            return Err(MoneyError::InvalidRatios);
         }
+        self.allocate_weights(&weights, strategy)
+    }
 
-        Ok(result)
+    /// Allocates the money pro rata to existing balances of the same
+    /// currency, e.g. to distribute a fee by account size.
+    ///
+    /// ```
+    /// use rust_money::{AllocationStrategy, Money};
+    ///
+    /// let balances = [Money::new(300_00, "USD"), Money::new(100_00, "USD"), Money::new(0, "USD")];
+    /// let fees = Money::new(-10_01, "USD").allocate_by_balances(&balances, AllocationStrategy::LargestRemainder).unwrap();
+    /// let amounts: Vec<i64> = fees.iter().map(Money::amount).collect();
+    /// assert_eq!(amounts, [-751, -250, 0]);
+    /// ```
+    pub fn allocate_by_balances(&self, balances: &[Money], strategy: AllocationStrategy) -> Result<Vec<Money>, MoneyError> {
+        let weights = balances
+            .iter()
+            .map(|b| {
+                self.assert_same_currency(b)?;
+                u128::try_from(b.amount).map_err(|_| MoneyError::NegativeRatio)
+            })
+            .collect::<Result<Vec<u128>, MoneyError>>()?;
+        self.allocate_weights(&weights, strategy)
     }
 
-    fn compare_money(&self, other: &Money) -> Result<i32, MoneyError> {
-        if let Err(err) = self.assert_same_currency(other) {
-            return Err(err);
-        }
+    /// Allocates the money among parties with optional floors and caps.
+    ///
+    /// The money is shared by ratio, with every part kept between the
+    /// party's floor and cap: a party whose share falls below its floor
+    /// receives the floor, and the shortfall is taken from the parties above
+    /// theirs in proportion to their ratios; what a party would receive
+    /// above its cap goes to the parties still below theirs. A floor does
+    /// not change the part of a party whose share already exceeds it.
+    /// Parties with a zero ratio receive their floor. Money that cannot be
+    /// placed because every party with a ratio is capped is returned as the
+    /// remainder, and [`MoneyError::InsufficientAmount`] is returned if the
+    /// floors add up to more than the money.
+    ///
+    /// ```
+    /// use rust_money::{AllocationStrategy, Money, Share};
+    ///
+    /// let usd = |amount| Money::new(amount, "USD");
+    /// let shares = [
+    ///     Share::new(1),
+    ///     Share::new(1).with_min(usd(400)),
+    ///     Share::new(2).with_max(usd(250)),
+    /// ];
+    /// // The third party is capped at 250 and the other 750 would be shared
+    /// // 375 / 375, so the second party is raised to its floor of 400.
+    /// let result = usd(1000).allocate_capped(&shares, AllocationStrategy::ToFirst).unwrap();
+    /// assert_eq!(result.parts, [usd(350), usd(400), usd(250)]);
+    /// assert_eq!(result.remainder, usd(0));
+    ///
+    /// // A floor below the pro-rata share has no effect.
+    /// let shares = [Share::new(1).with_min(usd(100)), Share::new(1)];
+    /// let result = usd(1000).allocate_capped(&shares, AllocationStrategy::ToFirst).unwrap();
+    /// assert_eq!(result.parts, [usd(500), usd(500)]);
+    /// ```
+    pub fn allocate_capped(&self, shares: &[Share], strategy: AllocationStrategy) -> Result<Allocation, MoneyError> {
+        let bound = |m: &Option<Money>| -> Result<Option<i64>, MoneyError> {
+            m.as_ref().map(|m| self.assert_same_currency(m).map(|_| m.amount)).transpose()
+        };
+        let ratios: Vec<u64> = shares.iter().map(|s| s.ratio).collect();
+        let mins = shares
+            .iter()
+            .map(|s| bound(&s.min).map(|m| m.unwrap_or(0)))
+            .collect::<Result<Vec<i64>, MoneyError>>()?;
+        let maxs = shares.iter().map(|s| bound(&s.max)).collect::<Result<Vec<Option<i64>>, MoneyError>>()?;
+        let (parts, remainder) = allocation::allocate_capped_amounts(self.amount, &ratios, &mins, &maxs, strategy)?;
+        Ok(Allocation {
+            parts: parts.into_iter().map(|amount| self.with_amount(amount)).collect(),
+            remainder: self.with_amount(remainder),
+        })
+    }
+
+    /// Fills tranches in priority order: each tranche receives up to its
+    /// capacity before the next one receives anything.
+    ///
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// let usd = |amount| Money::new(amount, "USD");
+    /// let result = usd(1000).allocate_waterfall(&[usd(600), usd(300), usd(500)]).unwrap();
+    /// assert_eq!(result.parts, [usd(600), usd(300), usd(100)]);
+    /// assert_eq!(result.remainder, usd(0));
+    /// ```
+    pub fn allocate_waterfall(&self, capacities: &[Money]) -> Result<Allocation, MoneyError> {
+        let capacities = capacities
+            .iter()
+            .map(|c| self.assert_same_currency(c).map(|_| c.amount))
+            .collect::<Result<Vec<i64>, MoneyError>>()?;
+        let (parts, remainder) = allocation::allocate_waterfall_amounts(self.amount, &capacities)?;
+        Ok(Allocation {
+            parts: parts.into_iter().map(|amount| self.with_amount(amount)).collect(),
+            remainder: self.with_amount(remainder),
+        })
+    }
+
+    fn allocate_weights(&self, weights: &[u128], strategy: AllocationStrategy) -> Result<Vec<Money>, MoneyError> {
+        let amounts = allocation::allocate_amounts(self.amount, weights, strategy)?;
+        Ok(amounts.into_iter().map(|amount| self.with_amount(amount)).collect())
+    }
+
+    /// Compares two values of the same currency, returning `1`, `0` or `-1`.
+    pub fn compare_money(&self, other: &Money) -> Result<i32, MoneyError> {
+        self.assert_same_currency(other)?;
 
         let cmp = self.compare(other);
         let result = match cmp {
@@ -212,17 +942,43 @@ impl Money {
     }
 
     // 表示为给定货币值的子单位 (float64)
-    fn as_major_units(&self) -> f64 {
-        self.currency.formatter().to_major_units(self.amount)
+    /// ```
+    /// use rust_money::Money;
+    ///
+    /// assert_eq!(Money::new(123456789, "EUR").as_major_units(), 1234567.89);
+    /// ```
+    pub fn as_major_units(&self) -> f64 {
+        self.currency().formatter().to_major_units(self.amount)
     }
 
+    // 以相同货币构造新的金额
+    fn with_amount(&self, amount: i64) -> Self {
+        Money {
+            amount,
+            currency: self.currency,
+        }
+    }
 }
 
-#[derive(Debug)]
-enum MoneyError {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyError {
     CurrencyMismatch,
     InvalidSplit,
     NoRatios,
+    Overflow,
+    DivisionByZero,
+    EmptySum,
+    RoundingNecessary,
+    InvalidIncrement,
+    InvalidRational,
+    InvalidRatios,
+    NegativeRatio,
+    NegativeAmount,
+    InvalidBounds,
+    InsufficientAmount,
+    UnknownCurrency(String),
+    InvalidDate,
+    NotFinite,
 }
 
 impl fmt::Display for MoneyError {
@@ -231,6 +987,20 @@ impl fmt::Display for MoneyError {
             MoneyError::CurrencyMismatch => write!(f, "Currency mismatch"),
             MoneyError::InvalidSplit => write!(f, "Invalid split value"),
             MoneyError::NoRatios => write!(f, "No ratios specified"),
+            MoneyError::Overflow => write!(f, "Amount overflow"),
+            MoneyError::DivisionByZero => write!(f, "Division by zero"),
+            MoneyError::EmptySum => write!(f, "Cannot sum an empty iterator of money"),
+            MoneyError::RoundingNecessary => write!(f, "Rounding necessary"),
+            MoneyError::InvalidIncrement => write!(f, "Invalid rounding increment"),
+            MoneyError::InvalidRational => write!(f, "Invalid rational number"),
+            MoneyError::InvalidRatios => write!(f, "Invalid ratios"),
+            MoneyError::NegativeRatio => write!(f, "Negative ratio"),
+            MoneyError::NegativeAmount => write!(f, "Negative amount"),
+            MoneyError::InvalidBounds => write!(f, "Invalid allocation bounds"),
+            MoneyError::InsufficientAmount => write!(f, "Amount is less than the sum of the minimums"),
+            MoneyError::UnknownCurrency(code) => write!(f, "Unknown currency code `{}`", code),
+            MoneyError::InvalidDate => write!(f, "Invalid date"),
+            MoneyError::NotFinite => write!(f, "Amount is not a finite number"),
         }
     }
 }
@@ -315,4 +1085,208 @@ mod tests {
         let m1 = Money::new(123456789,  "EUR").as_major_units();
         println!("{}", m1);
     }
+    #[test]
+    fn checked_arithmetic_at_bounds() {
+        let max = Money::new(i64::MAX, "USD");
+        let min = Money::new(i64::MIN, "USD");
+        let one = Money::new(1, "USD");
+        assert_eq!(max.checked_add(&one), Err(MoneyError::Overflow));
+        assert_eq!(min.checked_sub(&one), Err(MoneyError::Overflow));
+        assert_eq!(min.checked_neg(), Err(MoneyError::Overflow));
+        assert_eq!(min.checked_abs(), Err(MoneyError::Overflow));
+        assert_eq!(min.checked_mul(-1), Err(MoneyError::Overflow));
+        assert_eq!(max.checked_mul(-1).unwrap().amount(), -i64::MAX);
+        assert_eq!(max.checked_sub(&one).unwrap().amount(), i64::MAX - 1);
+        assert_eq!(max.subtract(&Money::new(-1, "USD")), Err(MoneyError::Overflow));
+        assert_eq!(max.checked_add(&Money::new(1, "EUR")), Err(MoneyError::CurrencyMismatch));
+    }
+    #[test]
+    fn saturating_arithmetic_at_bounds() {
+        let max = Money::new(i64::MAX, "USD");
+        let min = Money::new(i64::MIN, "USD");
+        let one = Money::new(1, "USD");
+        assert_eq!(max.saturating_add(&one).unwrap(), max);
+        assert_eq!(min.saturating_sub(&one).unwrap(), min);
+        assert_eq!(min.saturating_neg().amount(), i64::MAX);
+        assert_eq!(min.saturating_abs().amount(), i64::MAX);
+        assert_eq!(max.saturating_mul(2), max);
+        assert_eq!(max.saturating_mul(-2), min);
+        assert_eq!(max.saturating_add(&Money::new(1, "EUR")), Err(MoneyError::CurrencyMismatch));
+    }
+    #[test]
+    fn overflowing_arithmetic_at_bounds() {
+        let max = Money::new(i64::MAX, "USD");
+        let min = Money::new(i64::MIN, "USD");
+        let one = Money::new(1, "USD");
+        assert_eq!(max.overflowing_add(&one).unwrap(), (min, true));
+        assert_eq!(min.overflowing_sub(&one).unwrap(), (max, true));
+        assert_eq!(min.overflowing_neg(), (min, true));
+        assert_eq!(min.overflowing_abs(), (min, true));
+        assert_eq!(max.overflowing_mul(2), (Money::new(-2, "USD"), true));
+        assert_eq!(one.overflowing_mul(2), (Money::new(2, "USD"), false));
+    }
+    #[test]
+    fn display_at_bounds() {
+        assert_eq!(Money::new(i64::MIN, "USD").to_string(), "USD -$92,233,720,368,547,758.08");
+        assert_eq!(Money::new(i64::MAX, "USD").to_string(), "USD $92,233,720,368,547,758.07");
+        assert_eq!(Money::new(i64::MIN, "JPY").saturating_sub(&Money::new(1, "JPY")).unwrap().to_string(), "JPY -¥9,223,372,036,854,775,808");
+    }
+    #[test]
+    #[should_panic]
+    fn negative_of_min_panics() {
+        Money::new(i64::MIN, "USD").negative();
+    }
+    #[test]
+    fn round_to_increment_modes() {
+        let m = Money::new(-1237, "USD");
+        assert_eq!(m.round_to_increment(5, RoundingMode::HalfUp).unwrap().amount(), -1235);
+        assert_eq!(m.round_to_increment(5, RoundingMode::Floor).unwrap().amount(), -1240);
+        assert_eq!(m.round_to_increment(5, RoundingMode::Unnecessary), Err(MoneyError::RoundingNecessary));
+        assert_eq!(Money::new(-1235, "USD").round_to_increment(5, RoundingMode::Unnecessary).unwrap().amount(), -1235);
+        assert_eq!(m.round_to_increment(0, RoundingMode::HalfUp), Err(MoneyError::InvalidIncrement));
+        assert_eq!(Money::new(i64::MAX, "USD").round_to_increment(10, RoundingMode::Ceiling), Err(MoneyError::Overflow));
+    }
+    #[test]
+    fn cash_rounding() {
+        assert_eq!(Money::new(1012, "CHF").cash_rounded().amount(), 1010);
+        assert_eq!(Money::new(-1013, "CHF").cash_rounded().amount(), -1015);
+        assert_eq!(Money::new(1050, "DKK").cash_rounded().amount(), 1100);
+        assert_eq!(Money::new(1050, "DKK").cash_rounded_with(RoundingMode::HalfEven).unwrap().amount(), 1000);
+        assert_eq!(Money::new(12, "JPY").cash_rounded().amount(), 12);
+    }
+    #[test]
+    fn round_to_price_point() {
+        let m = Money::new(50, "USD");
+        assert_eq!(m.round_to_price_point(99, RoundingMode::HalfUp).unwrap().amount(), 99);
+        assert_eq!(m.round_to_price_point(99, RoundingMode::Floor).unwrap().amount(), -1);
+        assert_eq!(m.round_to_price_point(100, RoundingMode::HalfUp), Err(MoneyError::InvalidIncrement));
+        assert_eq!(Money::new(12, "JPY").round_to_price_point(0, RoundingMode::HalfUp).unwrap().amount(), 12);
+    }
+    #[test]
+    fn multiply_exact_residue() {
+        let m = Money::new(-1001, "USD");
+        let factor: Rational = "0.875".parse().unwrap();
+        for mode in [RoundingMode::HalfUp, RoundingMode::Floor, RoundingMode::Ceiling] {
+            let r = m.multiply_exact(factor, mode).unwrap();
+            // money + residue 必须等于精确值 -1001 * 7 / 8
+            let residue = r.residue.numer() * 8 / r.residue.denom();
+            assert_eq!(r.money.amount() as i128 * 8 + residue, -7007, "{:?}", mode);
+            assert!(r.residue.numer().abs() < r.residue.denom());
+        }
+        assert_eq!(m.multiply_exact(Rational::from_integer(2), RoundingMode::Unnecessary).unwrap().residue, Rational::from_integer(0));
+        assert_eq!(m.divide_exact(Rational::from_integer(0), RoundingMode::HalfUp), Err(MoneyError::DivisionByZero));
+        assert_eq!(Money::new(i64::MAX, "USD").multiply_exact("1.5".parse().unwrap(), RoundingMode::HalfUp), Err(MoneyError::Overflow));
+    }
+    #[test]
+    fn allocate_large_amounts() {
+        let parts = Money::new(i64::MAX, "USD").allocate(&[u32::MAX, u32::MAX, 1]).unwrap();
+        assert_eq!(parts.iter().map(|p| p.amount() as i128).sum::<i128>(), i64::MAX as i128);
+        let parts = Money::new(i64::MIN, "USD").allocate_u64(&[u64::MAX, 1, u64::MAX], AllocationStrategy::LargestRemainder).unwrap();
+        assert_eq!(parts.iter().map(|p| p.amount() as i128).sum::<i128>(), i64::MIN as i128);
+    }
+    #[test]
+    fn allocate_validation() {
+        let m = Money::new(1000, "USD");
+        let negative = [Money::new(5, "USD"), Money::new(-1, "USD")];
+        assert_eq!(m.allocate_by_balances(&negative, AllocationStrategy::ToFirst), Err(MoneyError::NegativeRatio));
+        let mixed = [Money::new(5, "USD"), Money::new(1, "EUR")];
+        assert_eq!(m.allocate_by_balances(&mixed, AllocationStrategy::ToFirst), Err(MoneyError::CurrencyMismatch));
+        let zero = [Money::new(0, "USD")];
+        assert_eq!(m.allocate_by_balances(&zero, AllocationStrategy::ToFirst), Err(MoneyError::InvalidRatios));
+        let percentages = [Rational::new(-10, 1).unwrap(), Rational::from_integer(110)];
+        assert_eq!(m.allocate_percentages(&percentages, AllocationStrategy::ToFirst), Err(MoneyError::NegativeRatio));
+        let thirds = [Rational::new(100, 3).unwrap(); 3];
+        let parts = m.allocate_percentages(&thirds, AllocationStrategy::ToLast).unwrap();
+        assert_eq!(parts.iter().map(Money::amount).collect::<Vec<_>>(), [333, 333, 334]);
+        assert_eq!(m.allocate_u64(&[], AllocationStrategy::ToFirst), Err(MoneyError::NoRatios));
+    }
+    #[test]
+    fn unknown_currency_codes() {
+        assert_eq!(Money::try_new(1, "XYZ"), Err(MoneyError::UnknownCurrency("XYZ".to_string())));
+        assert_eq!(Money::new_from_float_with(1.0, "usdx", RoundingMode::Floor), Err(MoneyError::UnknownCurrency("USDX".to_string())));
+        assert_eq!(Money::new(1, "USD").convert("XYZ", 1, 0, RoundingMode::Floor), Err(MoneyError::UnknownCurrency("XYZ".to_string())));
+    }
+    #[test]
+    #[should_panic(expected = "Unknown currency code `XYZ`")]
+    fn new_panics_on_unknown_currency() {
+        Money::new(1, "XYZ");
+    }
+    #[test]
+    fn money_is_copy_and_small() {
+        assert!(std::mem::size_of::<Money>() <= 16);
+        let m = Money::new(1001, "USD");
+        let copy = m;
+        assert_eq!(m, copy);
+        assert!(Arc::ptr_eq(&Money::new(1, "usd").currency(), &m.currency()));
+        let parts = m.split(3).unwrap();
+        assert!(parts.iter().all(|p| p.same_currency(&m)));
+    }
+    #[test]
+    fn constants_without_registry() {
+        let empty = Currencies::scoped(Currencies::new());
+        assert_eq!(Money::try_new(100, USD), Err(MoneyError::UnknownCurrency("USD".to_string())));
+        let m = Money::new(100, USD);
+        assert_eq!(m.to_string(), "USD $1.00");
+        assert_eq!(Money::new(100, CurrencyCode::JPY).to_string(), "JPY ¥100");
+        assert_eq!(Money::new_from_float(1.5, EUR).amount(), 150);
+        drop(empty);
+
+        let _scope = Currencies::scoped(Currencies::default());
+        Currencies::unregister("USD");
+        assert_eq!(Money::new(100, CurrencyCode::USD), m);
+        // 其他代码仍然需要在注册表中
+        assert!(std::panic::catch_unwind(|| Money::new(1, "XYZ")).is_err());
+    }
+    #[test]
+    fn money_outlives_its_definition() {
+        let xtu = || Currency::new_all("XTU", "¤", "", "$1", ".", ",", 2);
+        // 货币被注销后
+        let _scope = Currencies::scoped(Currencies::default());
+        Currencies::register(xtu());
+        let m = Money::new(-12345, "XTU");
+        assert_eq!(m.to_string(), "XTU -¤123.45");
+        Currencies::unregister("XTU");
+        assert_eq!(m.to_string(), "XTU -123.45 XTU");
+        assert_eq!(m.format_locale("de"), "-123,45\u{a0}XTU");
+        assert_eq!(m.round_with(RoundingMode::HalfUp).map(|r| r.amount()), Ok(-12300));
+        assert_eq!(m.as_major_units(), -123.45);
+
+        // 在另一线程上使用
+        Currencies::register(xtu());
+        let m = Money::new(150, "XTU");
+        let other = std::thread::spawn(move || (m.to_string(), m.currency().fraction()));
+        assert_eq!(other.join().unwrap(), ("XTU 1.50 XTU".to_string(), 2));
+
+        // 作用域结束后
+        let m = {
+            let _inner = Currencies::scoped(Currencies::default());
+            Currencies::register(Currency::new_all("XTV", "¤", "", "1 $", ".", ",", 3));
+            Money::new(1500, "XTV")
+        };
+        assert_eq!(m.to_string(), "XTV 1.500 XTV");
+        assert_eq!(m.cash_rounded(), m);
+
+        // 重新定义小数位数后，已有的金额保持原来的含义
+        let m = Money::new(150, "XTU");
+        Currencies::register(Currency::new_all("XTU", "¤", "", "1 $", ".", ",", 3));
+        assert_eq!(m.to_string(), "XTU 1.50 XTU");
+        assert_eq!(Money::new(150, "XTU").to_string(), "XTU 0.150 ¤");
+        assert!(!m.same_currency(&Money::new(150, "XTU")));
+        // 内置货币退回内置定义
+        let usd = Money::new(150, "USD");
+        Currencies::register(Currency::new_all("USD", "$", "840", "$1", ".", ",", 3));
+        assert_eq!(usd.to_string(), "USD $1.50");
+    }
+    #[test]
+    fn money_from_str() {
+        for m in [Money::new(-123456, "USD"), Money::new(5, "JPY"), Money::new(1000001, "BYN"), Money::new(-7, "KWD"), Money::new(i64::MIN, "USD")] {
+            assert_eq!(m.to_string().parse(), Ok(m));
+        }
+        assert_eq!("usd 1".parse(), Ok(Money::new(100, "USD")));
+        assert_eq!("USD".parse::<Money>(), Err(ParseError::Empty));
+        assert_eq!("USD 1,00".parse::<Money>(), Err(ParseError::InvalidGrouping));
+        assert_eq!("USD €1".parse::<Money>(), Err(ParseError::UnexpectedCharacter { position: 4, found: '€' }));
+        assert_eq!("JPY ¥1.5".parse::<Money>(), Err(ParseError::ExcessFractionDigits));
+        assert_eq!(Money::parse_with("JPY ¥1.5", RoundingMode::HalfEven), Ok(Money::new(2, "JPY")));
+    }
 }
@@ -0,0 +1,657 @@
+//! Loading currency definitions from external files.
+//!
+//! Each loader parses a list of [`CurrencyDefinition`]s and merges them into
+//! a [`Currencies`] table according to a [`MergePolicy`]. The whole input is
+//! checked before anything is changed, so a malformed row leaves the table
+//! as it was.
+//!
+//! ```
+//! use rust_money::{Currencies, MergePolicy};
+//!
+//! let csv = "\
+//! code,numeric_code,fraction,grapheme,template
+//! XTS,963,2,¤,1 $
+//! JPY,,3,,
+//! ";
+//! let mut currencies = Currencies::default();
+//! assert_eq!(currencies.load_csv(csv, MergePolicy::Update), Ok(2));
+//! assert_eq!(currencies.get_currency_by_code("XTS").unwrap().grapheme(), "¤");
+//! // Only the given fields are overwritten
+//! let jpy = currencies.get_currency_by_code("JPY").unwrap();
+//! assert_eq!((jpy.fraction(), jpy.grapheme()), (3, "¥"));
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::currency::{Currencies, Currency};
+use crate::date::Date;
+use crate::formatter::Grouping;
+
+/// How a definition is combined with a currency of the same code that is
+/// already in the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergePolicy {
+    /// The fields given by the definition replace those of the existing
+    /// currency; the others are kept.
+    #[default]
+    Update,
+    /// The definition replaces the existing currency; fields it omits take
+    /// the same defaults as for a new currency.
+    Replace,
+    /// Existing currencies are left untouched; only new codes are added.
+    KeepExisting,
+    /// A definition for an existing code is an error.
+    Reject,
+}
+
+/// A currency as read from a file.
+///
+/// Only `code` is required. For a new currency `fraction` must be given as
+/// well; the other fields default to the code as symbol, the template
+/// `"1 $"`, `"."` as decimal and `","` as thousand separator.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CurrencyDefinition {
+    pub code: String,
+    #[serde(deserialize_with = "numeric_code")]
+    pub numeric_code: Option<String>,
+    pub fraction: Option<usize>,
+    pub cash_rounding: Option<i64>,
+    pub grapheme: Option<String>,
+    pub template: Option<String>,
+    pub decimal: Option<String>,
+    pub thousand: Option<String>,
+    /// A CLDR pattern such as `"#,##,##0"`.
+    pub grouping: Option<Grouping>,
+    pub name: Option<String>,
+    /// Space-separated ISO 3166 alpha-2 codes.
+    pub countries: Option<String>,
+    pub introduced: Option<Date>,
+    /// A withdrawn currency is added to the historical table.
+    pub withdrawn: Option<Date>,
+    pub successor: Option<String>,
+}
+
+// 数字代码可以写成字符串 "008" 或整数 8
+fn numeric_code<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Numeric {
+        Text(String),
+        Number(u16),
+    }
+    Ok(match Option::<Numeric>::deserialize(deserializer)? {
+        Some(Numeric::Text(text)) => Some(text),
+        Some(Numeric::Number(number)) => Some(format!("{:03}", number)),
+        None => None,
+    })
+}
+
+impl CurrencyDefinition {
+    pub fn new(code: &str) -> Self {
+        CurrencyDefinition {
+            code: code.to_string(),
+            ..Default::default()
+        }
+    }
+
+    // 在 base 的基础上应用给出的字段，并检查结果是否可用
+    fn apply(&self, base: Option<&Currency>) -> Result<Currency, String> {
+        if self.code.len() != 3 || !self.code.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return Err(format!("invalid code `{}`, expected three letters", self.code));
+        }
+        let fraction = match (self.fraction, base) {
+            (Some(fraction), _) => fraction,
+            (None, Some(base)) => base.fraction(),
+            (None, None) => return Err("missing fraction for a new currency".to_string()),
+        };
+        let field = |value: &Option<String>, existing: Option<&str>, default: &str| -> String {
+            value.as_deref().or(existing).unwrap_or(default).to_string()
+        };
+        let numeric_code = field(&self.numeric_code, base.map(Currency::numeric_code), "");
+        let grapheme = field(&self.grapheme, base.map(Currency::grapheme), &self.code.to_uppercase());
+        let template = field(&self.template, base.map(Currency::template), "1 $");
+        let decimal = field(&self.decimal, base.map(Currency::decimal), ".");
+        let thousand = field(&self.thousand, base.map(Currency::thousand), ",");
+        let cash_rounding = self.cash_rounding.or(base.map(Currency::cash_rounding)).unwrap_or(1);
+        let grouping = self.grouping.or(base.map(Currency::grouping)).unwrap_or_default();
+        let name = field(&self.name, base.map(Currency::name), "");
+        let countries = match (&self.countries, base) {
+            (Some(countries), _) => countries.clone(),
+            (None, Some(base)) => base.countries().collect::<Vec<_>>().join(" "),
+            (None, None) => String::new(),
+        };
+        let introduced = self.introduced.or(base.and_then(Currency::introduced));
+        let withdrawn = self.withdrawn.or(base.and_then(Currency::withdrawn));
+        let successor = self.successor.as_deref().or(base.and_then(Currency::successor));
+
+        if !numeric_code.is_empty() && (numeric_code.len() != 3 || !numeric_code.bytes().all(|b| b.is_ascii_digit())) {
+            return Err(format!("invalid numeric code `{}`, expected three digits", numeric_code));
+        }
+        // i64 最多容纳 18 位小数
+        if fraction > 18 {
+            return Err(format!("invalid fraction {}, expected at most 18", fraction));
+        }
+        if cash_rounding < 1 {
+            return Err(format!("invalid cash rounding {}, expected a positive increment", cash_rounding));
+        }
+        if !template.contains('1') {
+            return Err(format!("invalid template `{}`, expected `1` as the amount placeholder", template));
+        }
+        if fraction > 0 && decimal.is_empty() {
+            return Err("empty decimal separator for a currency with minor units".to_string());
+        }
+        if let Some(country) = countries.split_whitespace().find(|c| c.len() != 2 || !c.bytes().all(|b| b.is_ascii_uppercase())) {
+            return Err(format!("invalid country `{}`, expected an ISO 3166 alpha-2 code", country));
+        }
+        if let (Some(introduced), Some(withdrawn)) = (introduced, withdrawn) {
+            if withdrawn <= introduced {
+                return Err(format!("withdrawn on {} before being introduced on {}", withdrawn, introduced));
+            }
+        }
+        if let Some(successor) = successor {
+            if successor.len() != 3 || !successor.bytes().all(|b| b.is_ascii_alphabetic()) {
+                return Err(format!("invalid successor `{}`, expected three letters", successor));
+            }
+        }
+        let mut currency = Currency::new_all(&self.code, grapheme, numeric_code, template, decimal, thousand, fraction)
+            .with_cash_rounding(cash_rounding)
+            .with_grouping(grouping)
+            .with_name(name)
+            .with_countries(countries);
+        if let Some(introduced) = introduced {
+            currency = currency.with_introduced(introduced);
+        }
+        if let Some(withdrawn) = withdrawn {
+            currency = currency.with_withdrawn(withdrawn);
+        }
+        if let Some(successor) = successor {
+            currency = currency.with_successor(successor);
+        }
+        Ok(currency)
+    }
+}
+
+/// A malformed definition, with the position it was found at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+    /// The line (CSV, XML) or 1-based entry (JSON, TOML, [`Currencies::merge`])
+    /// of the definition; `0` when the input as a whole is malformed.
+    pub row: usize,
+    /// The code of the definition, when it could be read.
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl LoadError {
+    fn new(row: usize, code: Option<&str>, message: impl Into<String>) -> Self {
+        LoadError {
+            row,
+            code: code.filter(|c| !c.is_empty()).map(str::to_uppercase),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.row, &self.code) {
+            (0, _) => write!(f, "{}", self.message),
+            (row, Some(code)) => write!(f, "row {} ({}): {}", row, code, self.message),
+            (row, None) => write!(f, "row {}: {}", row, self.message),
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+impl Currencies {
+    /// Merges `definitions` into the table, returning how many currencies
+    /// were added or changed. Nothing is changed if any definition is
+    /// invalid or, with [`MergePolicy::Reject`], already present.
+    ///
+    /// ```
+    /// use rust_money::{Currencies, CurrencyDefinition, MergePolicy};
+    ///
+    /// let mut currencies = Currencies::default();
+    /// let chf = CurrencyDefinition { cash_rounding: Some(1), ..CurrencyDefinition::new("CHF") };
+    /// assert_eq!(currencies.merge(vec![chf.clone()], MergePolicy::KeepExisting), Ok(0));
+    /// assert!(currencies.merge(vec![chf.clone()], MergePolicy::Reject).is_err());
+    /// assert_eq!(currencies.merge(vec![chf], MergePolicy::Update), Ok(1));
+    /// assert_eq!(currencies.get_currency_by_code("CHF").unwrap().cash_rounding(), 1);
+    /// ```
+    pub fn merge(&mut self, definitions: Vec<CurrencyDefinition>, policy: MergePolicy) -> Result<usize, LoadError> {
+        self.merge_rows(definitions.into_iter().enumerate().map(|(i, d)| (i + 1, d)).collect(), policy)
+    }
+
+    fn merge_rows(&mut self, rows: Vec<(usize, CurrencyDefinition)>, policy: MergePolicy) -> Result<usize, LoadError> {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut currencies = Vec::new();
+        for (row, definition) in &rows {
+            let error = |message: String| LoadError::new(*row, Some(&definition.code), message);
+            if let Some(first) = seen.insert(definition.code.to_uppercase(), *row) {
+                return Err(error(format!("duplicate definition, first defined in row {}", first)));
+            }
+            let existing = self.get_currency_by_code(&definition.code);
+            let base = match (existing, policy) {
+                (Some(_), MergePolicy::KeepExisting) => continue,
+                (Some(_), MergePolicy::Reject) => return Err(error("currency is already defined".to_string())),
+                (Some(existing), MergePolicy::Update) => Some(existing.as_ref()),
+                _ => None,
+            };
+            currencies.push(definition.apply(base).map_err(error)?);
+        }
+        let count = currencies.len();
+        for currency in currencies {
+            self.add(currency);
+        }
+        Ok(count)
+    }
+
+    /// Loads definitions from CSV with a header row naming the columns,
+    /// which may be any of the fields of [`CurrencyDefinition`] in any
+    /// order; `code` is required.
+    ///
+    /// Unquoted values are trimmed and an empty one leaves the field unset;
+    /// quote values that need spaces, commas or to be empty, e.g. `" "` or
+    /// `""` as thousand separator. Blank lines and lines starting with `#`
+    /// are ignored.
+    pub fn load_csv(&mut self, input: &str, policy: MergePolicy) -> Result<usize, LoadError> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+        let (header_row, header) = lines.next().ok_or_else(|| LoadError::new(0, None, "missing header row"))?;
+        let columns = csv_fields(header)
+            .map_err(|message| LoadError::new(header_row, None, message))?
+            .into_iter()
+            .map(|(name, _)| match name.as_str() {
+                "code" | "numeric_code" | "fraction" | "cash_rounding" | "grapheme" | "template" | "decimal" | "thousand"
+                | "grouping" | "name" | "countries" | "introduced" | "withdrawn" | "successor" => Ok(name),
+                _ => Err(LoadError::new(header_row, None, format!("unknown column `{}`", name))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !columns.iter().any(|c| c == "code") {
+            return Err(LoadError::new(header_row, None, "missing `code` column"));
+        }
+
+        let mut rows = Vec::new();
+        for (row, line) in lines {
+            let fields = csv_fields(line).map_err(|message| LoadError::new(row, None, message))?;
+            let code = columns.iter().position(|c| c == "code").and_then(|i| fields.get(i)).map(|(v, _)| v.clone());
+            let code = code.as_deref();
+            if fields.len() != columns.len() {
+                let message = format!("expected {} fields, found {}", columns.len(), fields.len());
+                return Err(LoadError::new(row, code, message));
+            }
+            let mut definition = CurrencyDefinition::default();
+            for (column, (value, quoted)) in columns.iter().zip(fields) {
+                if value.is_empty() && !quoted {
+                    continue;
+                }
+                let invalid = || LoadError::new(row, code, format!("invalid {} `{}`", column, value));
+                match column.as_str() {
+                    "code" => definition.code = value,
+                    "numeric_code" => definition.numeric_code = Some(value),
+                    "fraction" => definition.fraction = Some(value.parse().map_err(|_| invalid())?),
+                    "cash_rounding" => definition.cash_rounding = Some(value.parse().map_err(|_| invalid())?),
+                    "grapheme" => definition.grapheme = Some(value),
+                    "template" => definition.template = Some(value),
+                    "decimal" => definition.decimal = Some(value),
+                    "thousand" => definition.thousand = Some(value),
+                    "grouping" => definition.grouping = Some(value.parse().map_err(|_| invalid())?),
+                    "name" => definition.name = Some(value),
+                    "countries" => definition.countries = Some(value),
+                    "introduced" => definition.introduced = Some(value.parse().map_err(|_| invalid())?),
+                    "withdrawn" => definition.withdrawn = Some(value.parse().map_err(|_| invalid())?),
+                    _ => definition.successor = Some(value),
+                }
+            }
+            rows.push((row, definition));
+        }
+        self.merge_rows(rows, policy)
+    }
+
+    /// Loads definitions from a JSON array of objects with the fields of
+    /// [`CurrencyDefinition`]; the numeric code may be a string or a number.
+    ///
+    /// ```
+    /// use rust_money::{Currencies, MergePolicy};
+    ///
+    /// let json = r#"[{"code": "XTS", "numeric_code": 963, "fraction": 2}]"#;
+    /// let mut currencies = Currencies::new();
+    /// assert_eq!(currencies.load_json(json, MergePolicy::Reject), Ok(1));
+    /// assert_eq!(currencies.get_currency_by_code("XTS").unwrap().numeric_code(), "963");
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn load_json(&mut self, input: &str, policy: MergePolicy) -> Result<usize, LoadError> {
+        let values: Vec<serde_json::Value> =
+            serde_json::from_str(input).map_err(|err| LoadError::new(0, None, err.to_string()))?;
+        let mut rows = Vec::new();
+        for (i, value) in values.into_iter().enumerate() {
+            let code = value.get("code").and_then(|c| c.as_str()).map(str::to_string);
+            let definition = CurrencyDefinition::deserialize(value)
+                .map_err(|err| LoadError::new(i + 1, code.as_deref(), err.to_string()))?;
+            rows.push((i + 1, definition));
+        }
+        self.merge_rows(rows, policy)
+    }
+
+    /// Loads definitions from TOML, one `[[currency]]` table per currency
+    /// with the fields of [`CurrencyDefinition`].
+    ///
+    /// ```
+    /// use rust_money::{Currencies, MergePolicy};
+    ///
+    /// let toml = r#"
+    /// [[currency]]
+    /// code = "XTS"
+    /// fraction = 3
+    /// grapheme = "¤"
+    /// "#;
+    /// let mut currencies = Currencies::new();
+    /// assert_eq!(currencies.load_toml(toml, MergePolicy::Reject), Ok(1));
+    /// assert_eq!(currencies.get_currency_by_code("XTS").unwrap().fraction(), 3);
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn load_toml(&mut self, input: &str, policy: MergePolicy) -> Result<usize, LoadError> {
+        let mut table: toml::Table = input.parse().map_err(|err: toml::de::Error| LoadError::new(0, None, err.to_string()))?;
+        let entries = match table.remove("currency") {
+            Some(toml::Value::Array(entries)) => entries,
+            Some(_) => return Err(LoadError::new(0, None, "`currency` must be an array of tables")),
+            None => Vec::new(),
+        };
+        if let Some(key) = table.keys().next() {
+            return Err(LoadError::new(0, None, format!("unknown key `{}`", key)));
+        }
+        let mut rows = Vec::new();
+        for (i, value) in entries.into_iter().enumerate() {
+            let code = value.get("code").and_then(|c| c.as_str()).map(str::to_string);
+            let definition: CurrencyDefinition = value
+                .try_into()
+                .map_err(|err: toml::de::Error| LoadError::new(i + 1, code.as_deref(), err.message()))?;
+            rows.push((i + 1, definition));
+        }
+        self.merge_rows(rows, policy)
+    }
+
+    /// Loads the alphabetic and numeric codes, minor units and names from
+    /// the ISO 4217 current currency list (`list-one.xml`) published by SIX.
+    ///
+    /// The list has one entry per country, so each currency appears once
+    /// per country using it; only its first entry is read. Entries without
+    /// a currency, such as Antarctica's, are skipped, and minor units of
+    /// `N.A.` are read as 0. The list carries no symbols or formats, so new
+    /// currencies get the defaults of [`CurrencyDefinition`].
+    ///
+    /// ```
+    /// use rust_money::{Currencies, MergePolicy};
+    ///
+    /// let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <ISO_4217 Pblshd="2024-06-25">
+    ///   <CcyTbl>
+    ///     <CcyNtry>
+    ///       <CtryNm>ZIMBABWE</CtryNm>
+    ///       <CcyNm>Zimbabwe Gold</CcyNm>
+    ///       <Ccy>ZWG</Ccy>
+    ///       <CcyNbr>924</CcyNbr>
+    ///       <CcyMnrUnts>2</CcyMnrUnts>
+    ///     </CcyNtry>
+    ///   </CcyTbl>
+    /// </ISO_4217>"#;
+    /// let mut currencies = Currencies::default();
+    /// assert_eq!(currencies.load_iso_xml(xml, MergePolicy::Update), Ok(1));
+    /// assert_eq!(currencies.currency_by_numeric(924).unwrap().code(), "ZWG");
+    /// ```
+    pub fn load_iso_xml(&mut self, input: &str, policy: MergePolicy) -> Result<usize, LoadError> {
+        if !input.contains("<CcyTbl") {
+            return Err(LoadError::new(0, None, "not an ISO 4217 currency list, missing <CcyTbl>"));
+        }
+        let mut rows: Vec<(usize, CurrencyDefinition)> = Vec::new();
+        let mut rest = input;
+        while let Some(start) = rest.find("<CcyNtry>") {
+            let offset = input.len() - rest.len() + start;
+            let row = input[..offset].matches('\n').count() + 1;
+            let entry = &rest[start..];
+            let end = entry
+                .find("</CcyNtry>")
+                .ok_or_else(|| LoadError::new(row, None, "unterminated <CcyNtry>"))?;
+            let entry = &entry[..end];
+            rest = &rest[start + end..];
+
+            let Some(code) = xml_element(entry, "Ccy") else { continue };
+            let code = code.trim();
+            if rows.iter().any(|(_, d)| d.code == code) {
+                continue;
+            }
+            let numeric_code = xml_element(entry, "CcyNbr")
+                .map(|n| n.trim().to_string())
+                .ok_or_else(|| LoadError::new(row, Some(code), "missing <CcyNbr>"))?;
+            let fraction = match xml_element(entry, "CcyMnrUnts").map(str::trim) {
+                Some("N.A.") => 0,
+                Some(units) => units
+                    .parse()
+                    .map_err(|_| LoadError::new(row, Some(code), format!("invalid <CcyMnrUnts> `{}`", units)))?,
+                None => return Err(LoadError::new(row, Some(code), "missing <CcyMnrUnts>")),
+            };
+            rows.push((
+                row,
+                CurrencyDefinition {
+                    numeric_code: Some(numeric_code),
+                    fraction: Some(fraction),
+                    name: xml_element(entry, "CcyNm").map(|name| xml_unescape(name.trim())),
+                    ..CurrencyDefinition::new(code)
+                },
+            ));
+        }
+        self.merge_rows(rows, policy)
+    }
+}
+
+// 解析一行 CSV，返回每个字段的值以及它是否带引号
+fn csv_fields(line: &str) -> Result<Vec<(String, bool)>, String> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        let mut value = String::new();
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let quoted = chars.next_if_eq(&'"').is_some();
+        if quoted {
+            loop {
+                match chars.next() {
+                    Some('"') if chars.next_if_eq(&'"').is_some() => value.push('"'),
+                    Some('"') => break,
+                    Some(c) => value.push(c),
+                    None => return Err("unterminated quoted field".to_string()),
+                }
+            }
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_some_and(|c| *c != ',') {
+                return Err(format!("unexpected character after quoted field `{}`", value));
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',') {
+                if c == '"' {
+                    return Err("unexpected quote in unquoted field".to_string());
+                }
+                value.push(c);
+            }
+            value = value.trim_end().to_string();
+        }
+        fields.push((value, quoted));
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// 取出第一个 <name> 元素的文本内容
+fn xml_element<'a>(entry: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let mut rest = entry;
+    loop {
+        let start = rest.find(&open)?;
+        rest = &rest[start + open.len()..];
+        // 排除名字以 name 开头的其他元素，例如 <CcyNbr> 之于 <Ccy>
+        if rest.starts_with(['>', ' ', '\t', '\r', '\n']) {
+            let content = &rest[rest.find('>')? + 1..];
+            return Some(&content[..content.find(&close)?]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_csv_fields() {
+        let fields = csv_fields;
+        assert_eq!(
+            fields(r#"EUR, "1 $" ,"",,"a ""b""""#).unwrap(),
+            [("EUR".to_string(), false), ("1 $".to_string(), true), ("".to_string(), true), ("".to_string(), false), (r#"a "b""#.to_string(), true)]
+        );
+        assert_eq!(fields(r#""EUR"#), Err("unterminated quoted field".to_string()));
+        assert!(fields(r#""EUR"x,1"#).is_err());
+        assert!(fields(r#"E"UR,1"#).is_err());
+    }
+
+    #[test]
+    fn test_csv_errors() {
+        let mut currencies = Currencies::default();
+        let load = |c: &mut Currencies, csv: &str| c.load_csv(csv, MergePolicy::Update).map_err(|e| e.to_string());
+        assert_eq!(load(&mut currencies, ""), Err("missing header row".to_string()));
+        assert_eq!(load(&mut currencies, "code,symbol\n"), Err("row 1: unknown column `symbol`".to_string()));
+        assert_eq!(load(&mut currencies, "fraction\n2\n"), Err("row 1: missing `code` column".to_string()));
+        let csv = "# comment\ncode,fraction\n\nXTS,2\nxtt,two\n";
+        assert_eq!(load(&mut currencies, csv), Err("row 5 (XTT): invalid fraction `two`".to_string()));
+        assert_eq!(load(&mut currencies, "code,fraction\nXTS,2,3\n"), Err("row 2 (XTS): expected 2 fields, found 3".to_string()));
+        assert_eq!(load(&mut currencies, "code\nXTS\n"), Err("row 2 (XTS): missing fraction for a new currency".to_string()));
+        assert_eq!(load(&mut currencies, "code,fraction\nXT,2\n"), Err("row 2 (XT): invalid code `XT`, expected three letters".to_string()));
+        assert_eq!(
+            load(&mut currencies, "code,template\nUSD,$\n"),
+            Err("row 2 (USD): invalid template `$`, expected `1` as the amount placeholder".to_string())
+        );
+        assert_eq!(
+            load(&mut currencies, "code,fraction\nXTS,2\nxts,3\n"),
+            Err("row 3 (XTS): duplicate definition, first defined in row 2".to_string())
+        );
+        // 出错时不做任何修改
+        assert!(currencies.get_currency_by_code("XTS").is_none());
+    }
+
+    #[test]
+    fn test_merge_policies() {
+        let definitions = || vec![CurrencyDefinition { grapheme: Some("US$".to_string()), ..CurrencyDefinition::new("usd") }];
+        let mut currencies = Currencies::default();
+        assert_eq!(currencies.merge(definitions(), MergePolicy::KeepExisting), Ok(0));
+        assert_eq!(currencies.get_currency_by_code("USD").unwrap().grapheme(), "$");
+        let err = currencies.merge(definitions(), MergePolicy::Reject).unwrap_err();
+        assert_eq!((err.row, err.code.as_deref()), (1, Some("USD")));
+        assert_eq!(currencies.merge(definitions(), MergePolicy::Replace).unwrap_err().message, "missing fraction for a new currency");
+        assert_eq!(currencies.merge(definitions(), MergePolicy::Update), Ok(1));
+        let usd = currencies.get_currency_by_code("USD").unwrap();
+        assert_eq!((usd.grapheme(), usd.template(), usd.numeric_code(), usd.fraction()), ("US$", "$1", "840", 2));
+        let replaced = vec![CurrencyDefinition { fraction: Some(3), ..CurrencyDefinition::new("USD") }];
+        assert_eq!(currencies.merge(replaced, MergePolicy::Replace), Ok(1));
+        let usd = currencies.get_currency_by_code("USD").unwrap();
+        assert_eq!((usd.grapheme(), usd.template(), usd.numeric_code(), usd.fraction()), ("USD", "1 $", "", 3));
+        assert_eq!(usd.formatter().format(-1234567), "-1,234.567 USD");
+    }
+
+    #[test]
+    fn test_reload_frees_old_definitions() {
+        let mut currencies = Currencies::default();
+        currencies.load_csv("code,fraction,grapheme\nXTS,2,¤\n", MergePolicy::Replace).unwrap();
+        let first = Arc::downgrade(currencies.get_currency_by_code("XTS").unwrap());
+        currencies.load_csv("code,fraction\nXTS,3\n", MergePolicy::Update).unwrap();
+        // 重新加载后旧定义被释放，符号索引中也不再保留
+        assert!(first.upgrade().is_none());
+        assert_eq!(currencies.currencies_by_grapheme("¤")[0].fraction(), 3);
+        let second = Arc::downgrade(currencies.get_currency_by_code("XTS").unwrap());
+        currencies.remove("XTS");
+        assert!(second.upgrade().is_none());
+
+        // 作用域注册表结束后，其中加载的定义也被释放
+        let mut scoped = Currencies::default();
+        scoped.load_csv("code,fraction\nXTS,2\n", MergePolicy::Replace).unwrap();
+        let loaded = Arc::downgrade(scoped.get_currency_by_code("XTS").unwrap());
+        let guard = Currencies::scoped(scoped);
+        assert_eq!(crate::Money::new(150, "XTS").to_string(), "XTS 1.50 XTS");
+        drop(guard);
+        assert!(loaded.upgrade().is_none());
+    }
+
+    #[test]
+    fn test_iso_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ISO_4217 Pblshd="2024-06-25">
+<CcyTbl>
+<CcyNtry><CtryNm>ANTARCTICA</CtryNm><CcyNm>No universal currency</CcyNm></CcyNtry>
+<CcyNtry>
+<CtryNm>BOLIVIA (PLURINATIONAL STATE OF)</CtryNm>
+<CcyNm IsFund="true">Mvdol</CcyNm>
+<Ccy>BOV</Ccy>
+<CcyNbr>984</CcyNbr>
+<CcyMnrUnts>2</CcyMnrUnts>
+</CcyNtry>
+<CcyNtry><CtryNm>GERMANY</CtryNm><CcyNm>Euro</CcyNm><Ccy>EUR</Ccy><CcyNbr>978</CcyNbr><CcyMnrUnts>2</CcyMnrUnts></CcyNtry>
+<CcyNtry><CtryNm>FRANCE</CtryNm><CcyNm>Euro</CcyNm><Ccy>EUR</Ccy><CcyNbr>978</CcyNbr><CcyMnrUnts>2</CcyMnrUnts></CcyNtry>
+<CcyNtry><CtryNm>INTERNATIONAL MONETARY FUND (IMF) </CtryNm><CcyNm>SDR (Special Drawing Right)</CcyNm><Ccy>XDR</Ccy><CcyNbr>960</CcyNbr><CcyMnrUnts>N.A.</CcyMnrUnts></CcyNtry>
+</CcyTbl>
+</ISO_4217>"#;
+        let mut currencies = Currencies::default();
+        assert_eq!(currencies.load_iso_xml(xml, MergePolicy::Update), Ok(3));
+        let bov = currencies.get_currency_by_code("BOV").unwrap();
+        assert_eq!((bov.numeric_code(), bov.fraction(), bov.grapheme(), bov.name()), ("984", 2, "BOV", "Mvdol"));
+        assert_eq!(currencies.get_currency_by_code("EUR").unwrap().grapheme(), "€");
+        assert_eq!(currencies.get_currency_by_code("XDR").unwrap().fraction(), 0);
+
+        assert_eq!(currencies.load_iso_xml("<html/>", MergePolicy::Update).unwrap_err().row, 0);
+        let bad = "<CcyTbl>\n<CcyNtry><Ccy>XTS</Ccy><CcyNbr>963</CcyNbr><CcyMnrUnts>two</CcyMnrUnts></CcyNtry></CcyTbl>";
+        assert_eq!(
+            currencies.load_iso_xml(bad, MergePolicy::Update).unwrap_err().to_string(),
+            "row 2 (XTS): invalid <CcyMnrUnts> `two`"
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json() {
+        let mut currencies = Currencies::default();
+        let json = r#"[{"code": "XTS", "fraction": 2}, {"code": "XTT", "fraction": -1}]"#;
+        let err = currencies.load_json(json, MergePolicy::Update).unwrap_err();
+        assert_eq!((err.row, err.code.as_deref()), (2, Some("XTT")));
+        let err = currencies.load_json(r#"[{"code": "XTS", "symbol": "¤"}]"#, MergePolicy::Update).unwrap_err();
+        assert!(err.message.contains("unknown field `symbol`"), "{}", err);
+        assert_eq!(currencies.load_json("{", MergePolicy::Update).unwrap_err().row, 0);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml() {
+        let mut currencies = Currencies::default();
+        let toml = "[[currency]]\ncode = \"CHF\"\ncash_rounding = 10\n\n[[currency]]\ncode = \"XTS\"\nnumeric_code = 963\n";
+        let err = currencies.load_toml(toml, MergePolicy::Update).unwrap_err();
+        assert_eq!(err.to_string(), "row 2 (XTS): missing fraction for a new currency");
+        assert_eq!(currencies.load_toml(&toml.replace("963", "963\nfraction = 2"), MergePolicy::Update), Ok(2));
+        assert_eq!(currencies.get_currency_by_code("CHF").unwrap().cash_rounding(), 10);
+        assert!(currencies.load_toml("currencies = []", MergePolicy::Update).is_err());
+    }
+}
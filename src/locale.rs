@@ -0,0 +1,266 @@
+//! Locale-aware formatting from vendored CLDR data.
+//!
+//! A currency's own separators and template describe how it is usually
+//! written in its home country. [`Formatter::for_locale`] instead takes the
+//! separators, pattern and symbols of the reader's locale from a subset of
+//! the CLDR number and currency data in `data/cldr-locales.tsv` and
+//! `data/cldr-symbols.tsv`:
+//!
+//! ```
+//! use rust_money::Money;
+//!
+//! let m = Money::new(123450, "EUR");
+//! assert_eq!(m.format_locale("de"), "1.234,50\u{a0}€");
+//! assert_eq!(m.format_locale("fr"), "1\u{202f}234,50\u{a0}€");
+//! assert_eq!(m.format_locale("en-IE"), "€1,234.50");
+//! // Locales without data fall back to the currency's own format
+//! assert_eq!(m.format_locale("xx"), "€1,234.50");
+//! ```
+//!
+//! Amounts are written in the locale's default numbering system, which a
+//! `-u-nu-` extension in the tag overrides:
+//!
+//! ```
+//! use rust_money::Money;
+//!
+//! let m = Money::new(123450, "SAR");
+//! assert_eq!(m.format_locale("ar"), "\u{200f}١٬٢٣٤٫٥٠\u{a0}ر.س.\u{200f}");
+//! assert_eq!(m.format_locale("ar-u-nu-latn"), "\u{200f}1,234.50\u{a0}ر.س.\u{200f}");
+//! assert_eq!(Money::new(123450, "INR").format_locale("hi-IN-u-nu-deva"), "₹१,२३४.५०");
+//! ```
+
+use crate::currency::Currency;
+use crate::formatter::{Digits, Formatter, Grouping};
+
+/// Which symbol [`Formatter::for_locale`] puts in the pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SymbolStyle {
+    /// The locale's symbol, which tells similar currencies apart, e.g.
+    /// `US$` and `CA$` in English outside the United States.
+    #[default]
+    Standard,
+    /// The shortest symbol, e.g. `$` for any dollar.
+    Narrow,
+    /// The ISO 4217 code.
+    Code,
+}
+
+// 区域的数字符号与货币模式，由 build.rs 生成
+struct Locale {
+    tag: &'static str,
+    parent: &'static str,
+    numbers: &'static str,
+    decimal: &'static str,
+    group: &'static str,
+    minus: &'static str,
+    pattern: &'static str,
+}
+
+// 区域中某种货币的符号，空串表示不覆盖父区域
+struct Symbol {
+    locale: &'static str,
+    currency: &'static str,
+    symbol: &'static str,
+    narrow: &'static str,
+}
+
+include!(concat!(env!("OUT_DIR"), "/locales.rs"));
+
+impl Formatter {
+    /// A formatter for `currency` as written in `locale`, a BCP 47 tag such
+    /// as `"de-CH"` (`_` is accepted too). Subtags that are not in the data
+    /// are dropped from the end, so `"de-DE"` uses `"de"`. Returns `None` if
+    /// nothing is left of the tag.
+    ///
+    /// The amount keeps the currency's number of fraction digits and is
+    /// grouped as in the locale's pattern, e.g. in lakhs and crores for
+    /// `"en-IN"`. Symbols that are letters get a no-break space next to the
+    /// number, as CLDR prescribes. The digits are the locale's default
+    /// [`Digits`] unless the tag names others with a Unicode extension,
+    /// as in `"th-u-nu-thai"`; unsupported numbering systems are ignored.
+    ///
+    /// ```
+    /// use rust_money::{Currency, Formatter, SymbolStyle};
+    ///
+    /// let usd = Currency::from_code("USD").unwrap();
+    /// let f = Formatter::for_locale(&usd, "en-CA", SymbolStyle::Standard).unwrap();
+    /// assert_eq!(f.format(-123456), "-US$1,234.56");
+    /// let f = Formatter::for_locale(&usd, "en-CA", SymbolStyle::Narrow).unwrap();
+    /// assert_eq!(f.format(-123456), "-$1,234.56");
+    /// let f = Formatter::for_locale(&Currency::from_code("CHF").unwrap(), "de-CH", SymbolStyle::Standard).unwrap();
+    /// assert_eq!(f.format(-123456), "CHF-1’234.56");
+    /// assert_eq!(f.parse("CHF-1’234.56"), Ok(-123456));
+    /// assert!(Formatter::for_locale(&usd, "tlh", SymbolStyle::Standard).is_none());
+    ///
+    /// let f = Formatter::for_locale(&Currency::from_code("JPY").unwrap(), "ja-u-nu-fullwide", SymbolStyle::Standard).unwrap();
+    /// assert_eq!(f.format(1234), "￥１,２３４");
+    /// assert_eq!(f.parse("￥１,２３４"), Ok(1234));
+    /// ```
+    pub fn for_locale(currency: &Currency, locale: &str, style: SymbolStyle) -> Option<Formatter> {
+        let (tag, digits) = numbering_system(locale);
+        let default = find(&tag)?;
+        let digits = digits.unwrap_or(Digits::from_cldr(default.numbers).unwrap());
+        // 数字系统的符号取自同一区域的对应行，没有时用 latn 行
+        let rows = || LOCALES.iter().filter(|l| l.tag == default.tag);
+        let locale = rows()
+            .find(|l| Digits::from_cldr(l.numbers) == Some(digits))
+            .or_else(|| rows().find(|l| l.numbers == "latn"))
+            .unwrap_or(default);
+        let symbol = symbol(locale, currency, style);
+        let (positive, negative) = match locale.pattern.split_once(';') {
+            Some((positive, negative)) => (positive, Some(negative)),
+            None => (locale.pattern, None),
+        };
+        let mut template = template(positive, &symbol);
+        match negative {
+            Some(negative) => template = format!("{};{}", template, self::template(negative, &symbol).replace('-', locale.minus)),
+            None if locale.minus != "-" => template = format!("{};{}{}", template, locale.minus, template),
+            None => {}
+        }
+        // 模式中没有分组符时不分组
+        let (group, grouping) = match positive.parse() {
+            Ok(grouping) => (locale.group, grouping),
+            Err(_) => ("", Grouping::default()),
+        };
+        Some(
+            Formatter::new(currency.fraction, locale.decimal, group, &symbol, &template)
+                .with_grouping(grouping)
+                .with_digits(digits),
+        )
+    }
+}
+
+// 拆出 Unicode 扩展中的数字系统：ar-EG-u-nu-latn → ("ar-eg", Some(Latin))
+fn numbering_system(tag: &str) -> (String, Option<Digits>) {
+    let tag = tag.replace('_', "-").to_ascii_lowercase();
+    let subtags: Vec<&str> = tag.split('-').collect();
+    let end = subtags.iter().position(|s| s.len() == 1).unwrap_or(subtags.len());
+    let digits = match subtags.get(end) {
+        Some(&"u") => subtags[end + 1..]
+            .iter()
+            .take_while(|s| s.len() > 1)
+            .skip_while(|s| **s != "nu")
+            .nth(1)
+            .and_then(|s| Digits::from_cldr(s)),
+        _ => None,
+    };
+    (subtags[..end].join("-"), digits)
+}
+
+// 按 BCP 47 的截断规则查找：de-CH-1996 → de-CH → de
+fn find(tag: &str) -> Option<&'static Locale> {
+    let tag = tag.replace('_', "-");
+    let mut tag = tag.as_str();
+    loop {
+        if let Some(locale) = LOCALES.iter().find(|l| l.tag.eq_ignore_ascii_case(tag)) {
+            return Some(locale);
+        }
+        tag = &tag[..tag.rfind('-')?];
+    }
+}
+
+// 沿父区域查找符号；标准符号缺省为 ISO 代码，窄符号缺省为内置表的 grapheme
+fn symbol(locale: &'static Locale, currency: &Currency, style: SymbolStyle) -> String {
+    let inherited = |pick: fn(&Symbol) -> &'static str| {
+        let mut locale = Some(locale);
+        while let Some(l) = locale {
+            let found = SYMBOLS.iter().find(|s| s.locale == l.tag && s.currency == currency.code()).map(pick);
+            if let Some(symbol) = found.filter(|s| !s.is_empty()) {
+                return Some(symbol);
+            }
+            locale = LOCALES.iter().find(|p| p.tag == l.parent);
+        }
+        None
+    };
+    let standard = || inherited(|s| s.symbol).unwrap_or(currency.code());
+    match style {
+        SymbolStyle::Standard => standard().to_string(),
+        SymbolStyle::Narrow => inherited(|s| s.narrow)
+            .or(Some(currency.grapheme()).filter(|g| !g.is_empty()))
+            .unwrap_or_else(standard)
+            .to_string(),
+        SymbolStyle::Code => currency.code().to_string(),
+    }
+}
+
+// 把 CLDR 模式（如 "¤#,##0.00"）转换为 Formatter 的模板（如 "$1"）
+fn template(pattern: &str, symbol: &str) -> String {
+    let start = pattern.find(['#', '0']).unwrap();
+    let end = pattern.rfind(['#', '0']).unwrap() + 1;
+    let (mut prefix, mut suffix) = (pattern[..start].to_string(), pattern[end..].to_string());
+    // CLDR 的 currencySpacing：符号紧挨数字的一端不是符号字符时插入不换行空格，这里以字母、数字和句点近似
+    let spaced = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '.');
+    if prefix.ends_with('¤') && spaced(symbol.chars().next_back()) {
+        prefix.push('\u{a0}');
+    }
+    if suffix.starts_with('¤') && spaced(symbol.chars().next()) {
+        suffix.insert(0, '\u{a0}');
+    }
+    format!("{}1{}", prefix.replace('¤', "$"), suffix.replace('¤', "$"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Money;
+
+    #[test]
+    fn test_format_locale() {
+        let m = Money::new(-123456, "EUR");
+        assert_eq!(m.format_locale("de"), "-1.234,56\u{a0}€");
+        assert_eq!(m.format_locale("de_AT"), "-€\u{a0}1\u{a0}234,56");
+        assert_eq!(m.format_locale("nl"), "€\u{a0}-1.234,56");
+        assert_eq!(m.format_locale("fi"), "\u{2212}1\u{a0}234,56\u{a0}€");
+        assert_eq!(m.format_locale("en-GB"), "-€1,234.56");
+        let usd = Money::new(123456, "USD");
+        assert_eq!(usd.format_locale("en"), "$1,234.56");
+        assert_eq!(usd.format_locale("en-US"), "$1,234.56");
+        assert_eq!(usd.format_locale("en-GB"), "US$1,234.56");
+        assert_eq!(usd.format_locale("en-AU"), "USD\u{a0}1,234.56");
+        assert_eq!(usd.format_locale("fr-CA"), "1\u{a0}234,56\u{a0}$\u{a0}US");
+        assert_eq!(usd.format_locale("ja"), "$1,234.56");
+        assert_eq!(usd.format_locale_with("en-GB", SymbolStyle::Narrow), "$1,234.56");
+        assert_eq!(usd.format_locale_with("de", SymbolStyle::Code), "1.234,56\u{a0}USD");
+        assert_eq!(Money::new(1234, "JPY").format_locale("ja"), "￥1,234");
+        assert_eq!(Money::new(1234, "JPY").format_locale("en"), "JP¥1,234");
+        assert_eq!(Money::new(123456, "CHF").format_locale("en"), "CHF\u{a0}1,234.56");
+        assert_eq!(Money::new(1_000_000_000, "USD").format_locale("en-IN"), "US$1,00,00,000.00");
+        assert_eq!(Money::new(-1_000_000_000, "INR").format_locale("hi"), "-₹1,00,00,000.00");
+        assert_eq!(Money::new(1_000_000_000, "INR").format_locale("de"), "10.000.000,00\u{a0}₹");
+        assert_eq!(Money::new(123456, "SEK").format_locale_with("sv", SymbolStyle::Narrow), "1\u{a0}234,56\u{a0}kr");
+        // 没有 CLDR 符号时，标准符号用代码，窄符号用内置的 grapheme
+        let ngn = Money::new(100, "NGN");
+        assert_eq!(ngn.format_locale("en"), "NGN\u{a0}1.00");
+        assert_eq!(ngn.format_locale_with("en", SymbolStyle::Narrow), "₦1.00");
+    }
+    #[test]
+    fn test_numbering_systems() {
+        let m = Money::new(-123456, "IRR");
+        assert_eq!(m.format_locale("fa"), "\u{200e}\u{2212}\u{200e}ریال\u{a0}۱٬۲۳۴٫۵۶");
+        assert_eq!(m.format_locale("fa-IR-u-nu-latn"), "\u{200e}\u{2212}\u{200e}ریال\u{a0}1,234.56");
+        assert_eq!(Money::new(123456, "BDT").format_locale("bn"), "১,২৩৪.৫৬৳");
+        assert_eq!(Money::new(123456, "INR").format_locale("mr"), "₹१,२३४.५६");
+        assert_eq!(Money::new(123456, "THB").format_locale("th"), "฿1,234.56");
+        assert_eq!(Money::new(123456, "THB").format_locale("th-TH-u-ca-buddhist-nu-thai"), "฿๑,๒๓๔.๕๖");
+        // 没有 arab 行的区域沿用 latn 的符号
+        assert_eq!(Money::new(123456, "EUR").format_locale("de-u-nu-arab"), "١.٢٣٤,٥٦\u{a0}€");
+        // 不支持的数字系统和其他扩展被忽略
+        assert_eq!(Money::new(123456, "EUR").format_locale("de-u-nu-hanidec"), "1.234,56\u{a0}€");
+        assert_eq!(Money::new(123456, "EUR").format_locale("de-x-nu-arab"), "1.234,56\u{a0}€");
+        assert_eq!(numbering_system("ar_EG-u-ca-islamic-nu-latn"), ("ar-eg".to_string(), Some(Digits::Latin)));
+        assert_eq!(numbering_system("en-u-nu"), ("en".to_string(), None));
+    }
+    #[test]
+    fn test_locale_round_trip() {
+        for tag in LOCALES.iter().map(|l| format!("{}-u-nu-{}", l.tag, l.numbers)) {
+            for code in ["EUR", "USD", "CHF", "JPY", "KWD"] {
+                for style in [SymbolStyle::Standard, SymbolStyle::Narrow, SymbolStyle::Code] {
+                    let f = Formatter::for_locale(&Currency::from_code(code).unwrap(), &tag, style).unwrap();
+                    for amount in [0, 7, -7, 123456789, -123456789, i64::MIN] {
+                        assert_eq!(f.parse(&f.format(amount)), Ok(amount), "{} {} {:?}", tag, code, style);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! `std::ops` implementations for [`Money`].
+//!
+//! Operations between two `Money` values return `Result<Money, MoneyError>`
+//! so that a currency mismatch or an overflow is reported instead of hidden:
+//!
+//! ```
+//! use rust_money::{Money, MoneyError};
+//!
+//! let a = Money::new(150, "USD");
+//! let b = Money::new(50, "USD");
+//! assert_eq!(&a + &b, Ok(Money::new(200, "USD")));
+//! assert_eq!(a - b, Ok(Money::new(100, "USD")));
+//! assert_eq!(a + Money::new(1, "EUR"), Err(MoneyError::CurrencyMismatch));
+//! ```
+//!
+//! Operations with an `i64` and the `*Assign` operators cannot return an
+//! error, so they panic on overflow, on a zero divisor or (for
+//! `AddAssign`/`SubAssign`) on a currency mismatch, just like the integer
+//! operators do. Use the `checked_*` methods when the input is untrusted.
+
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
+
+use crate::{Money, MoneyError};
+
+impl Add for Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn add(self, rhs: Money) -> Self::Output {
+        self.checked_add(&rhs)
+    }
+}
+
+impl Add<&Money> for Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn add(self, rhs: &Money) -> Self::Output {
+        self.checked_add(rhs)
+    }
+}
+
+impl Add<&Money> for &Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn add(self, rhs: &Money) -> Self::Output {
+        self.checked_add(rhs)
+    }
+}
+
+impl Add<Money> for &Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn add(self, rhs: Money) -> Self::Output {
+        self.checked_add(&rhs)
+    }
+}
+
+impl Sub for Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn sub(self, rhs: Money) -> Self::Output {
+        self.checked_sub(&rhs)
+    }
+}
+
+impl Sub<&Money> for Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn sub(self, rhs: &Money) -> Self::Output {
+        self.checked_sub(rhs)
+    }
+}
+
+impl Sub<&Money> for &Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn sub(self, rhs: &Money) -> Self::Output {
+        self.checked_sub(rhs)
+    }
+}
+
+impl Sub<Money> for &Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn sub(self, rhs: Money) -> Self::Output {
+        self.checked_sub(&rhs)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        self.negative()
+    }
+}
+
+impl Neg for &Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        self.negative()
+    }
+}
+
+impl Mul<i64> for Money {
+    type Output = Money;
+
+    fn mul(self, rhs: i64) -> Money {
+        self.multiply(rhs)
+    }
+}
+
+impl Mul<i64> for &Money {
+    type Output = Money;
+
+    fn mul(self, rhs: i64) -> Money {
+        self.multiply(rhs)
+    }
+}
+
+impl Mul<Money> for i64 {
+    type Output = Money;
+
+    fn mul(self, rhs: Money) -> Money {
+        rhs.multiply(self)
+    }
+}
+
+impl Mul<&Money> for i64 {
+    type Output = Money;
+
+    fn mul(self, rhs: &Money) -> Money {
+        rhs.multiply(self)
+    }
+}
+
+/// Truncating division, so that `(m / n) * n + m % n == m`.
+impl Div<i64> for Money {
+    type Output = Money;
+
+    fn div(self, rhs: i64) -> Money {
+        (&self).div(rhs)
+    }
+}
+
+impl Div<i64> for &Money {
+    type Output = Money;
+
+    fn div(self, rhs: i64) -> Money {
+        self.checked_div(rhs).expect("attempt to divide money by zero or with overflow")
+    }
+}
+
+/// The minor units left over by the truncating `Div<i64>`.
+impl Rem<i64> for Money {
+    type Output = Money;
+
+    fn rem(self, rhs: i64) -> Money {
+        (&self).rem(rhs)
+    }
+}
+
+impl Rem<i64> for &Money {
+    type Output = Money;
+
+    fn rem(self, rhs: i64) -> Money {
+        self.checked_rem(rhs).expect("attempt to calculate the remainder of money with a divisor of zero or with overflow")
+    }
+}
+
+impl AddAssign<&Money> for Money {
+    fn add_assign(&mut self, rhs: &Money) {
+        *self = self.checked_add(rhs).expect("money addition failed");
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        *self += &rhs;
+    }
+}
+
+impl SubAssign<&Money> for Money {
+    fn sub_assign(&mut self, rhs: &Money) {
+        *self = self.checked_sub(rhs).expect("money subtraction failed");
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        *self -= &rhs;
+    }
+}
+
+impl MulAssign<i64> for Money {
+    fn mul_assign(&mut self, rhs: i64) {
+        *self = self.multiply(rhs);
+    }
+}
+
+impl DivAssign<i64> for Money {
+    fn div_assign(&mut self, rhs: i64) {
+        *self = *self / rhs;
+    }
+}
+
+impl RemAssign<i64> for Money {
+    fn rem_assign(&mut self, rhs: i64) {
+        *self = *self % rhs;
+    }
+}
+
+/// Sums money of a single currency with [`Money::add`].
+///
+/// An empty iterator yields `MoneyError::EmptySum`, since there is no
+/// currency to give the zero result.
+///
+/// ```
+/// use rust_money::{Money, MoneyError};
+///
+/// let lines = vec![Money::new(100, "EUR"), Money::new(250, "EUR")];
+/// assert_eq!(lines.iter().sum::<Result<Money, _>>(), Ok(Money::new(350, "EUR")));
+/// assert_eq!(Vec::<Money>::new().into_iter().sum::<Result<Money, _>>(), Err(MoneyError::EmptySum));
+/// ```
+impl<'a> Sum<&'a Money> for Result<Money, MoneyError> {
+    fn sum<I: Iterator<Item = &'a Money>>(mut iter: I) -> Self {
+        let first = iter.next().ok_or(MoneyError::EmptySum)?;
+        iter.try_fold(*first, |total, m| total.add(m))
+    }
+}
+
+impl Sum<Money> for Result<Money, MoneyError> {
+    fn sum<I: Iterator<Item = Money>>(mut iter: I) -> Self {
+        let first = iter.next().ok_or(MoneyError::EmptySum)?;
+        iter.try_fold(first, |total, m| total.add(&m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 借用形式的实现也需要覆盖
+    #[test]
+    #[allow(clippy::op_ref)]
+    fn test_binary_ops() {
+        let a = Money::new(700, "USD");
+        let b = Money::new(200, "USD");
+        assert_eq!(&a + &b, Ok(Money::new(900, "USD")));
+        assert_eq!(&a + b, Ok(Money::new(900, "USD")));
+        assert_eq!(a + b, Ok(Money::new(900, "USD")));
+        assert_eq!(&a - &b, Ok(Money::new(500, "USD")));
+        assert_eq!(a - b, Ok(Money::new(500, "USD")));
+        assert_eq!(-&a, Money::new(-700, "USD"));
+        assert_eq!(&a * 3, Money::new(2100, "USD"));
+        assert_eq!(3 * &a, Money::new(2100, "USD"));
+        assert_eq!(&a - &Money::new(1, "GBP"), Err(MoneyError::CurrencyMismatch));
+        assert_eq!(&Money::new(i64::MAX, "USD") + &Money::new(1, "USD"), Err(MoneyError::Overflow));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        for amount in [-701, -700, -1, 0, 1, 700, 701] {
+            for divisor in [-3, -2, 1, 2, 3] {
+                let m = Money::new(amount, "USD");
+                let q = m / divisor;
+                let r = m % divisor;
+                assert_eq!(q.amount() * divisor + r.amount(), amount);
+            }
+        }
+        assert_eq!(Money::new(700, "USD").checked_div(0), Err(MoneyError::DivisionByZero));
+        assert_eq!(Money::new(i64::MIN, "USD").checked_div(-1), Err(MoneyError::Overflow));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_div_by_zero_panics() {
+        let _ = Money::new(700, "USD") / 0;
+    }
+
+    #[test]
+    fn test_assign_ops() {
+        let mut m = Money::new(100, "USD");
+        m += Money::new(50, "USD");
+        m -= &Money::new(20, "USD");
+        m *= 4;
+        assert_eq!(m, Money::new(520, "USD"));
+        m /= 3;
+        assert_eq!(m, Money::new(173, "USD"));
+        m %= 10;
+        assert_eq!(m, Money::new(3, "USD"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_assign_mismatch_panics() {
+        let mut m = Money::new(100, "USD");
+        m += Money::new(50, "EUR");
+    }
+
+    #[test]
+    fn test_sum() {
+        let lines = vec![Money::new(1, "USD"), Money::new(2, "USD"), Money::new(3, "USD")];
+        assert_eq!(lines.iter().sum::<Result<Money, _>>(), Ok(Money::new(6, "USD")));
+        assert_eq!(lines.into_iter().sum::<Result<Money, _>>(), Ok(Money::new(6, "USD")));
+        let mixed = [Money::new(1, "USD"), Money::new(2, "EUR")];
+        assert_eq!(mixed.iter().sum::<Result<Money, _>>(), Err(MoneyError::CurrencyMismatch));
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::rounding::pow10;
+use crate::MoneyError;
+
+/// An exact fraction used to scale [`Money`](crate::Money) without going
+/// through floating point.
+///
+/// Rationals are always stored in lowest terms with a positive denominator.
+///
+/// ```
+/// use rust_money::Rational;
+///
+/// let vat: Rational = "1.19".parse().unwrap();
+/// assert_eq!((vat.numer(), vat.denom()), (119, 100));
+/// assert_eq!("1/3".parse::<Rational>().unwrap(), Rational::new(2, 6).unwrap());
+/// assert_eq!("19%".parse::<Rational>().unwrap(), Rational::new(19, 100).unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+impl Rational {
+    pub fn new(numer: i128, denom: i128) -> Result<Self, MoneyError> {
+        if denom == 0 {
+            return Err(MoneyError::DivisionByZero);
+        }
+        let g = gcd(numer, denom);
+        let (mut numer, mut denom) = (numer / g, denom / g);
+        if denom < 0 {
+            numer = numer.checked_neg().ok_or(MoneyError::Overflow)?;
+            denom = denom.checked_neg().ok_or(MoneyError::Overflow)?;
+        }
+        Ok(Rational { numer, denom })
+    }
+
+    pub fn from_integer(value: i64) -> Self {
+        Rational { numer: value as i128, denom: 1 }
+    }
+
+    /// Builds `mantissa * 10^-scale`, so `(875, 3)` is 0.875.
+    pub fn from_decimal(mantissa: i128, scale: u32) -> Result<Self, MoneyError> {
+        Rational::new(mantissa, pow10(scale)?)
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer == 0
+    }
+
+    pub fn recip(&self) -> Result<Self, MoneyError> {
+        Rational::new(self.denom, self.numer)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational::from_integer(value)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+/// Parses an integer (`"3"`), a decimal (`"-0.875"`), a fraction (`"1/3"`)
+/// or a percentage (`"7.25%"`).
+impl FromStr for Rational {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(percent) = s.strip_suffix('%') {
+            let value: Rational = percent.trim_end().parse()?;
+            return Rational::new(value.numer, value.denom.checked_mul(100).ok_or(MoneyError::Overflow)?);
+        }
+        if let Some((numer, denom)) = s.split_once('/') {
+            let numer = parse_decimal(numer.trim())?;
+            let denom = parse_decimal(denom.trim())?;
+            if denom.is_zero() {
+                return Err(MoneyError::DivisionByZero);
+            }
+            let n = numer.numer.checked_mul(denom.denom).ok_or(MoneyError::Overflow)?;
+            let d = numer.denom.checked_mul(denom.numer).ok_or(MoneyError::Overflow)?;
+            return Rational::new(n, d);
+        }
+        parse_decimal(s)
+    }
+}
+
+// 解析形如 "-12.345" 的十进制数
+fn parse_decimal(s: &str) -> Result<Rational, MoneyError> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    if int_part.is_empty() && frac_part.is_empty() {
+        return Err(MoneyError::InvalidRational);
+    }
+    let mut mantissa: i128 = 0;
+    for c in int_part.chars().chain(frac_part.chars()) {
+        let digit = c.to_digit(10).ok_or(MoneyError::InvalidRational)? as i128;
+        mantissa = mantissa.checked_mul(10).and_then(|m| m.checked_add(digit)).ok_or(MoneyError::Overflow)?;
+    }
+    let scale = u32::try_from(frac_part.len()).map_err(|_| MoneyError::Overflow)?;
+    Rational::from_decimal(if negative { -mantissa } else { mantissa }, scale)
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // gcd 不超过 |denom|，因此除 i128::MIN 本身外都能放回 i128
+    i128::try_from(a).unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalization() {
+        let r = Rational::new(6, -4).unwrap();
+        assert_eq!((r.numer(), r.denom()), (-3, 2));
+        assert_eq!(Rational::new(0, -5).unwrap(), Rational::from_integer(0));
+        assert_eq!(Rational::new(1, 0), Err(MoneyError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("0.875".parse(), Rational::new(7, 8));
+        assert_eq!("-.5".parse(), Rational::new(-1, 2));
+        assert_eq!("+12".parse(), Ok(Rational::from_integer(12)));
+        assert_eq!("1.5/0.5".parse(), Ok(Rational::from_integer(3)));
+        assert_eq!("7.25 %".parse(), Rational::new(29, 400));
+        assert_eq!("1/0".parse::<Rational>(), Err(MoneyError::DivisionByZero));
+        assert_eq!("".parse::<Rational>(), Err(MoneyError::InvalidRational));
+        assert_eq!("1.2.3".parse::<Rational>(), Err(MoneyError::InvalidRational));
+        assert_eq!("1e3".parse::<Rational>(), Err(MoneyError::InvalidRational));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Rational::new(-2, 6).unwrap().to_string(), "-1/3");
+        assert_eq!(Rational::from_integer(4).to_string(), "4");
+    }
+}
@@ -0,0 +1,339 @@
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+
+use crate::currency::{Currencies, Currency};
+
+// 进程级的货币注册表，首次使用时由内置表初始化
+fn global() -> &'static RwLock<Currencies> {
+    static GLOBAL: OnceLock<RwLock<Currencies>> = OnceLock::new();
+    GLOBAL.get_or_init(|| RwLock::new(Currencies::default()))
+}
+
+// 串行化对全局注册表的修改，保证读出、修改、写回之间没有其他修改
+static GLOBAL_UPDATE: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    // 当前线程上由 Currencies::scoped 安装的注册表，栈顶生效
+    static SCOPED: RefCell<Vec<Currencies>> = const { RefCell::new(Vec::new()) };
+    // 当前线程是否正在修改注册表，用于发现嵌套的修改
+    static UPDATING: Cell<bool> = const { Cell::new(false) };
+    // 当前线程上正在运行的 with_registry 闭包数，期间持有借用或读锁
+    static READING: Cell<usize> = const { Cell::new(0) };
+}
+
+// 标记当前线程正在修改注册表，结束（包括 panic）时清除
+struct Updating;
+
+impl Updating {
+    fn start() -> Updating {
+        assert!(
+            !UPDATING.get() && READING.get() == 0,
+            "the currency registry cannot be changed from inside `Currencies::with_registry` or `Currencies::with_registry_mut`"
+        );
+        UPDATING.set(true);
+        Updating
+    }
+}
+
+impl Drop for Updating {
+    fn drop(&mut self) {
+        UPDATING.set(false);
+    }
+}
+
+// 标记当前线程正在读取注册表，结束（包括 panic）时清除
+struct Reading;
+
+impl Reading {
+    fn start() -> Reading {
+        READING.set(READING.get() + 1);
+        Reading
+    }
+}
+
+impl Drop for Reading {
+    fn drop(&mut self) {
+        READING.set(READING.get() - 1);
+    }
+}
+
+// 直接修改当前线程可见的注册表；f 运行时持有借用或锁，不能再访问注册表
+fn modify<R>(f: impl FnOnce(&mut Currencies) -> R) -> R {
+    let _updating = Updating::start();
+    SCOPED.with(|scoped| match scoped.borrow_mut().last_mut() {
+        Some(currencies) => f(currencies),
+        None => {
+            let _update = GLOBAL_UPDATE.lock().unwrap_or_else(PoisonError::into_inner);
+            f(&mut global().write().unwrap_or_else(PoisonError::into_inner))
+        }
+    })
+}
+
+/// The process-wide currency registry.
+///
+/// [`Money`](crate::Money) constructors and deserialization resolve codes
+/// through this registry. It is built from the built-in table on first use
+/// and can be changed at runtime; reads take a shared lock, so concurrent
+/// lookups do not block each other.
+///
+/// ```
+/// use rust_money::{Currencies, Currency, Money};
+///
+/// Currencies::register(Currency::new_all("XTS", "¤", "963", "1 $", ".", ",", 2));
+/// assert_eq!(Money::new(1050, "XTS").to_string(), "XTS 10.50 ¤");
+/// ```
+impl Currencies {
+    /// Runs `f` with the registry visible to the current thread.
+    ///
+    /// `f` may look up currencies, but changing the registry from inside
+    /// `f`, e.g. through [`Currencies::register`], panics.
+    pub fn with_registry<R>(f: impl FnOnce(&Currencies) -> R) -> R {
+        let _reading = Reading::start();
+        SCOPED.with(|scoped| match scoped.borrow().last() {
+            Some(currencies) => f(currencies),
+            None => f(&global().read().unwrap_or_else(PoisonError::into_inner)),
+        })
+    }
+
+    /// Runs `f` with mutable access to the registry visible to the current
+    /// thread, e.g. to load currency definitions into it:
+    ///
+    /// ```
+    /// use rust_money::{Currencies, MergePolicy, Money};
+    ///
+    /// let _scope = Currencies::scoped(Currencies::default());
+    /// Currencies::with_registry_mut(|c| c.load_csv("code,fraction\nXTS,3\n", MergePolicy::Update)).unwrap();
+    /// assert_eq!(Money::new(1500, "XTS").to_string(), "XTS 1.500 XTS");
+    /// ```
+    ///
+    /// `f` works on a copy of the registry that replaces it once `f`
+    /// returns; if `f` panics, the registry is left unchanged. No lock is
+    /// held while `f` runs, so it may create [`Money`](crate::Money) values
+    /// or call [`Currencies::lookup`], which see the registry as it was
+    /// before the call. Changing the registry from inside `f`, through
+    /// [`Currencies::register`] or a nested `with_registry_mut`, panics.
+    /// Other changes to the global registry wait until `f` returns.
+    pub fn with_registry_mut<R>(f: impl FnOnce(&mut Currencies) -> R) -> R {
+        let _updating = Updating::start();
+        let depth = SCOPED.with(|scoped| scoped.borrow().len());
+        if depth > 0 {
+            let mut copy = SCOPED.with(|scoped| scoped.borrow()[depth - 1].clone());
+            let result = f(&mut copy);
+            SCOPED.with(|scoped| {
+                if let Some(currencies) = scoped.borrow_mut().get_mut(depth - 1) {
+                    *currencies = copy;
+                }
+            });
+            result
+        } else {
+            let _update = GLOBAL_UPDATE.lock().unwrap_or_else(PoisonError::into_inner);
+            let mut copy = global().read().unwrap_or_else(PoisonError::into_inner).clone();
+            let result = f(&mut copy);
+            *global().write().unwrap_or_else(PoisonError::into_inner) = copy;
+            result
+        }
+    }
+
+    /// Looks up a currency by its alphabetic code in the registry.
+    pub fn lookup(code: &str) -> Option<Arc<Currency>> {
+        Self::with_registry(|c| c.get_currency_by_code(code).cloned())
+    }
+
+    /// Adds a currency to the registry, returning the definition it replaced.
+    pub fn register(currency: Currency) -> Option<Arc<Currency>> {
+        modify(|c| {
+            let previous = c.remove(&currency.code);
+            c.add(currency);
+            previous
+        })
+    }
+
+    /// Removes a currency from the registry, returning its definition.
+    pub fn unregister(code: &str) -> Option<Arc<Currency>> {
+        modify(|c| c.remove(code))
+    }
+
+    /// Replaces the registry for the current thread until the returned guard
+    /// is dropped, so tests can register currencies without affecting other
+    /// threads.
+    ///
+    /// ```
+    /// use rust_money::{Currencies, Currency};
+    ///
+    /// {
+    ///     let _scope = Currencies::scoped(Currencies::new());
+    ///     assert!(Currencies::lookup("USD").is_none());
+    ///     Currencies::register(Currency::new_all("XTS", "¤", "963", "1 $", ".", ",", 2));
+    ///     assert!(Currencies::lookup("XTS").is_some());
+    /// }
+    /// assert!(Currencies::lookup("USD").is_some());
+    /// ```
+    pub fn scoped(currencies: Currencies) -> ScopedRegistry {
+        let depth = SCOPED.with(|scoped| {
+            let mut scoped = scoped.borrow_mut();
+            scoped.push(currencies);
+            scoped.len()
+        });
+        ScopedRegistry { depth, _not_send: PhantomData }
+    }
+}
+
+/// Guard returned by [`Currencies::scoped`]; restores the previous registry
+/// of the current thread when dropped.
+///
+/// # Panics
+///
+/// Guards must be dropped in the reverse order of their creation. Dropping
+/// a guard while a scope created after it is still active removes both
+/// scopes and panics.
+#[must_use = "the scoped registry is removed as soon as the guard is dropped"]
+pub struct ScopedRegistry {
+    // 该作用域在栈中的位置（从 1 开始）
+    depth: usize,
+    // 作用域绑定在创建它的线程上
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ScopedRegistry {
+    fn drop(&mut self) {
+        let len = SCOPED.with(|scoped| {
+            let mut scoped = scoped.borrow_mut();
+            let len = scoped.len();
+            scoped.truncate(self.depth - 1);
+            len
+        });
+        // 外层作用域先结束时，内层作用域已随之移除
+        if len > self.depth && !std::thread::panicking() {
+            panic!("scoped registries must be dropped in the reverse order of their creation");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Money;
+
+    #[test]
+    fn test_global_register_and_remove() {
+        assert!(Currencies::lookup("XRA").is_none());
+        Currencies::register(Currency::new_all("XRA", "R", "", "1$", ".", ",", 3));
+        let handle = std::thread::spawn(|| Money::new(1, "XRA").currency().fraction());
+        assert_eq!(handle.join().unwrap(), 3);
+        assert_eq!(Currencies::unregister("xra").map(|c| c.fraction()), Some(3));
+        assert!(Currencies::lookup("XRA").is_none());
+    }
+
+    #[test]
+    fn test_scoped_is_thread_local() {
+        let _scope = Currencies::scoped(Currencies::default());
+        Currencies::register(Currency::new_all("XRB", "R", "", "1$", ".", ",", 1));
+        let previous = Currencies::register(Currency::new_all("USD", "$", "840", "$1", ".", ",", 3));
+        assert_eq!(previous.map(|c| c.fraction()), Some(2));
+        assert_eq!(Money::new(1, "USD").currency().fraction(), 3);
+        assert!(Currencies::lookup("XRB").is_some());
+        let other = std::thread::spawn(|| (Currencies::lookup("XRB").is_some(), Money::new(1, "USD").currency().fraction()));
+        assert_eq!(other.join().unwrap(), (false, 2));
+    }
+
+    #[test]
+    fn test_nested_scopes() {
+        let outer = Currencies::scoped(Currencies::new());
+        Currencies::register(Currency::new_all("XRC", "R", "", "1$", ".", ",", 1));
+        {
+            let _inner = Currencies::scoped(Currencies::default());
+            assert!(Currencies::lookup("XRC").is_none());
+            assert!(Currencies::lookup("EUR").is_some());
+        }
+        assert!(Currencies::lookup("XRC").is_some());
+        assert!(Currencies::lookup("EUR").is_none());
+        drop(outer);
+        assert!(Currencies::lookup("EUR").is_some());
+    }
+
+    #[test]
+    fn test_lookup_inside_with_registry_mut() {
+        let _scope = Currencies::scoped(Currencies::default());
+        let fraction = Currencies::with_registry_mut(|c| {
+            c.add(Currency::new_all("XRD", "R", "", "1$", ".", ",", 1));
+            // 闭包运行时不持有借用，查询看到的是修改前的注册表
+            assert!(Currencies::lookup("XRD").is_none());
+            Money::new(1, "USD").currency().fraction()
+        });
+        assert_eq!(fraction, 2);
+        assert!(Currencies::lookup("XRD").is_some());
+
+        // 闭包 panic 时注册表保持不变
+        let result = std::panic::catch_unwind(|| {
+            Currencies::with_registry_mut(|c| {
+                c.remove("XRD");
+                panic!("abort");
+            })
+        });
+        assert!(result.is_err());
+        assert!(Currencies::lookup("XRD").is_some());
+    }
+
+    #[test]
+    fn test_global_with_registry_mut_allows_lookups() {
+        let usd = Currencies::with_registry_mut(|c| {
+            c.add(Currency::new_all("XRE", "R", "", "1$", ".", ",", 1));
+            Money::new(1, "USD").to_string()
+        });
+        assert_eq!(usd, "USD $0.01");
+        assert_eq!(Currencies::unregister("XRE").map(|c| c.fraction()), Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be changed from inside")]
+    fn test_register_inside_with_registry_mut_panics() {
+        let _scope = Currencies::scoped(Currencies::default());
+        Currencies::with_registry_mut(|_| Currencies::register(Currency::new_all("XRF", "R", "", "1$", ".", ",", 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be changed from inside")]
+    fn test_register_inside_with_registry_panics() {
+        let _scope = Currencies::scoped(Currencies::default());
+        Currencies::with_registry(|_| Currencies::register(Currency::new_all("XRG", "R", "", "1$", ".", ",", 1)));
+    }
+
+    // 全局注册表上同样报错而不是死锁
+    #[test]
+    #[should_panic(expected = "cannot be changed from inside")]
+    fn test_unregister_inside_global_with_registry_panics() {
+        Currencies::with_registry(|_| Currencies::unregister("XRH"));
+    }
+
+    #[test]
+    fn test_with_registry_after_panic() {
+        let result = std::panic::catch_unwind(|| Currencies::with_registry(|_| panic!("abort")));
+        assert!(result.is_err());
+        // 闭包 panic 后不再视为正在读取
+        let _scope = Currencies::scoped(Currencies::new());
+        assert!(Currencies::register(Currency::new_all("XRI", "R", "", "1$", ".", ",", 1)).is_none());
+        assert_eq!(Currencies::with_registry(|c| c.len()), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "reverse order of their creation")]
+    fn test_scopes_dropped_out_of_order_panic() {
+        let outer = Currencies::scoped(Currencies::new());
+        let _inner = Currencies::scoped(Currencies::default());
+        drop(outer);
+    }
+
+    #[test]
+    fn test_scopes_dropped_out_of_order_are_both_removed() {
+        let result = std::panic::catch_unwind(|| {
+            let outer = Currencies::scoped(Currencies::new());
+            let inner = Currencies::scoped(Currencies::new());
+            drop(outer);
+            drop(inner);
+        });
+        assert!(result.is_err());
+        assert!(Currencies::lookup("USD").is_some());
+        SCOPED.with(|scoped| assert!(scoped.borrow().is_empty()));
+    }
+}
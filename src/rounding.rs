@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+
+use crate::MoneyError;
+
+/// How to round a value that falls between two representable amounts.
+///
+/// The `Half*` modes only differ when the value is exactly halfway; the
+/// other modes ignore the distance to the neighbours.
+///
+/// | mode           |  2.5 |  1.5 | 1.2 | -1.2 | -2.5 |
+/// |----------------|------|------|-----|------|------|
+/// | `HalfUp`       |  3   |  2   |  1  |  -1  |  -3  |
+/// | `HalfDown`     |  2   |  1   |  1  |  -1  |  -2  |
+/// | `HalfEven`     |  2   |  2   |  1  |  -1  |  -2  |
+/// | `HalfOdd`      |  3   |  1   |  1  |  -1  |  -3  |
+/// | `Ceiling`      |  3   |  2   |  2  |  -1  |  -2  |
+/// | `Floor`        |  2   |  1   |  1  |  -2  |  -3  |
+/// | `TowardZero`   |  2   |  1   |  1  |  -1  |  -2  |
+/// | `AwayFromZero` |  3   |  2   |  2  |  -2  |  -3  |
+///
+/// `Unnecessary` returns `MoneyError::RoundingNecessary` whenever the value
+/// is not already exact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RoundingMode {
+    /// Round to nearest, ties away from zero.
+    #[default]
+    HalfUp,
+    /// Round to nearest, ties toward zero.
+    HalfDown,
+    /// Round to nearest, ties to the even neighbour (banker's rounding).
+    HalfEven,
+    /// Round to nearest, ties to the odd neighbour.
+    HalfOdd,
+    /// Round toward positive infinity.
+    Ceiling,
+    /// Round toward negative infinity.
+    Floor,
+    /// Truncate.
+    TowardZero,
+    /// Round away from zero.
+    AwayFromZero,
+    /// Fail instead of rounding.
+    Unnecessary,
+}
+
+impl RoundingMode {
+    // 判断是否需要将截断后的商向远离零的方向调整一个单位
+    fn round_away(self, quotient: i128, remainder: i128, denominator: i128, positive: bool) -> Result<bool, MoneyError> {
+        let half = remainder.cmp(&(denominator - remainder));
+        Ok(match self {
+            RoundingMode::HalfUp => half != Ordering::Less,
+            RoundingMode::HalfDown => half == Ordering::Greater,
+            RoundingMode::HalfEven => half == Ordering::Greater || (half == Ordering::Equal && quotient % 2 != 0),
+            RoundingMode::HalfOdd => half == Ordering::Greater || (half == Ordering::Equal && quotient % 2 == 0),
+            RoundingMode::Ceiling => positive,
+            RoundingMode::Floor => !positive,
+            RoundingMode::TowardZero => false,
+            RoundingMode::AwayFromZero => true,
+            RoundingMode::Unnecessary => return Err(MoneyError::RoundingNecessary),
+        })
+    }
+}
+
+/// Divides `numerator` by `denominator`, rounding the quotient with `mode`.
+pub(crate) fn div_round(numerator: i128, denominator: i128, mode: RoundingMode) -> Result<i128, MoneyError> {
+    if denominator == 0 {
+        return Err(MoneyError::DivisionByZero);
+    }
+    let (numerator, denominator) = if denominator < 0 {
+        (numerator.checked_neg().ok_or(MoneyError::Overflow)?, denominator.checked_neg().ok_or(MoneyError::Overflow)?)
+    } else {
+        (numerator, denominator)
+    };
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return Ok(quotient);
+    }
+    if mode.round_away(quotient, remainder.abs(), denominator, numerator > 0)? {
+        Ok(quotient + numerator.signum())
+    } else {
+        Ok(quotient)
+    }
+}
+
+/// Computes `value * multiplier / divisor` exactly, rounding once at the end.
+pub(crate) fn mul_div_round(value: i128, multiplier: i128, divisor: i128, mode: RoundingMode) -> Result<i128, MoneyError> {
+    let product = value.checked_mul(multiplier).ok_or(MoneyError::Overflow)?;
+    div_round(product, divisor, mode)
+}
+
+/// Rescales a decimal `mantissa * 10^-scale` to `target_scale` fractional
+/// digits, rounding with `mode` when digits have to be dropped.
+pub(crate) fn rescale(mantissa: i128, scale: u32, target_scale: u32, mode: RoundingMode) -> Result<i128, MoneyError> {
+    if scale <= target_scale {
+        return pow10(target_scale - scale).and_then(|p| mantissa.checked_mul(p).ok_or(MoneyError::Overflow));
+    }
+    match pow10(scale - target_scale) {
+        Ok(divisor) => div_round(mantissa, divisor, mode),
+        // 10^excess 超出 i128 时，|mantissa| 必然小于除数的一半：按 ±1/3 的非零小数处理
+        Err(_) => div_round(mantissa.signum(), 3, mode),
+    }
+}
+
+pub(crate) fn pow10(exp: u32) -> Result<i128, MoneyError> {
+    10i128.checked_pow(exp).ok_or(MoneyError::Overflow)
+}
+
+pub(crate) fn to_i64(value: i128) -> Result<i64, MoneyError> {
+    i64::try_from(value).map_err(|_| MoneyError::Overflow)
+}
+
+/// Converts a finite float to a decimal `(mantissa, scale)` using its
+/// shortest round-trip representation, so `0.29` is read as `29 / 100`
+/// rather than as the binary value just below it.
+pub(crate) fn decimal_from_f64(value: f64) -> Result<(i128, u32), MoneyError> {
+    if !value.is_finite() {
+        return Err(MoneyError::NotFinite);
+    }
+    // f64 的 Display 不使用科学计数法，输出最短的可往返十进制表示
+    let repr = value.to_string();
+    let (negative, digits) = match repr.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, repr.as_str()),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    let frac_part = frac_part.trim_end_matches('0');
+    let int_part = int_part.trim_start_matches('0');
+    let mut mantissa: i128 = 0;
+    for c in int_part.chars().chain(frac_part.chars()) {
+        let digit = c.to_digit(10).expect("f64 display only yields decimal digits") as i128;
+        mantissa = mantissa.checked_mul(10).and_then(|m| m.checked_add(digit)).ok_or(MoneyError::Overflow)?;
+    }
+    let scale = u32::try_from(frac_part.len()).map_err(|_| MoneyError::Overflow)?;
+    Ok((if negative { -mantissa } else { mantissa }, scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [RoundingMode; 8] = [
+        RoundingMode::HalfUp,
+        RoundingMode::HalfDown,
+        RoundingMode::HalfEven,
+        RoundingMode::HalfOdd,
+        RoundingMode::Ceiling,
+        RoundingMode::Floor,
+        RoundingMode::TowardZero,
+        RoundingMode::AwayFromZero,
+    ];
+
+    #[test]
+    fn test_div_round_table() {
+        // 与 RoundingMode 文档中的表格一致
+        let expected: [[i128; 5]; 8] = [
+            [3, 2, 1, -1, -3],
+            [2, 1, 1, -1, -2],
+            [2, 2, 1, -1, -2],
+            [3, 1, 1, -1, -3],
+            [3, 2, 2, -1, -2],
+            [2, 1, 1, -2, -3],
+            [2, 1, 1, -1, -2],
+            [3, 2, 2, -2, -3],
+        ];
+        for (mode, row) in MODES.iter().zip(expected) {
+            let actual = [25, 15, 12, -12, -25].map(|n| div_round(n, 10, *mode).unwrap());
+            assert_eq!(actual, row, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_div_round_negative_denominator() {
+        assert_eq!(div_round(25, -10, RoundingMode::Floor), Ok(-3));
+        assert_eq!(div_round(-25, -10, RoundingMode::HalfEven), Ok(2));
+    }
+
+    #[test]
+    fn test_unnecessary() {
+        assert_eq!(div_round(20, 10, RoundingMode::Unnecessary), Ok(2));
+        assert_eq!(div_round(21, 10, RoundingMode::Unnecessary), Err(MoneyError::RoundingNecessary));
+        assert_eq!(div_round(1, 0, RoundingMode::HalfUp), Err(MoneyError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_decimal_from_f64() {
+        assert_eq!(decimal_from_f64(0.29), Ok((29, 2)));
+        assert_eq!(decimal_from_f64(-12.5), Ok((-125, 1)));
+        assert_eq!(decimal_from_f64(100.0), Ok((100, 0)));
+        assert_eq!(decimal_from_f64(f64::NAN), Err(MoneyError::NotFinite));
+        assert_eq!(decimal_from_f64(f64::INFINITY), Err(MoneyError::NotFinite));
+        assert_eq!(decimal_from_f64(f64::NEG_INFINITY), Err(MoneyError::NotFinite));
+        assert_eq!(decimal_from_f64(f64::MAX), Err(MoneyError::Overflow));
+        assert_eq!(rescale(1, 300, 2, RoundingMode::Ceiling), Ok(1));
+        assert_eq!(rescale(1, 300, 2, RoundingMode::HalfUp), Ok(0));
+    }
+}
@@ -0,0 +1,404 @@
+//! Serde support for [`Money`].
+//!
+//! `Money` is serialized as its amount in minor units together with the
+//! ISO 4217 code of its currency:
+//!
+//! ```json
+//! {"amount": 1234, "currency": "USD"}
+//! ```
+//!
+//! The currency's formatting details are not part of the wire format; they
+//! are looked up again from the [`Currencies`] registry when deserializing, and an
+//! unknown currency code is rejected.
+//!
+//! ```
+//! use rust_money::Money;
+//!
+//! let m = Money::new(1234, "USD");
+//! let json = serde_json::to_string(&m).unwrap();
+//! assert_eq!(json, r#"{"amount":1234,"currency":"USD"}"#);
+//! assert_eq!(serde_json::from_str::<Money>(&json).unwrap(), m);
+//! assert!(serde_json::from_str::<Money>(r#"{"amount":1,"currency":"XYZ"}"#).is_err());
+//! ```
+//!
+//! APIs that expect a different shape can pick one of the submodules with
+//! `#[serde(with = "...")]`, or for [`amount_only`], whose currency is not
+//! part of the value, with `serialize_with` and `deserialize_with`:
+//!
+//! | module          | JSON                                              |
+//! |-----------------|---------------------------------------------------|
+//! | [`minor_units`] | `{"amount": 1234, "currency": "USD"}`             |
+//! | [`major_units`] | `{"amount": "12.34", "currency": "USD"}`          |
+//! | [`lowercase`]   | `{"amount": 1234, "currency": "usd"}`             |
+//! | [`as_string`]   | `"12.34 USD"`                                     |
+//! | [`amount_only`] | `1234`, with the currency given separately        |
+//!
+//! ```
+//! use rust_money::Money;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Charge {
+//!     #[serde(with = "rust_money::serde::lowercase")]
+//!     price: Money,
+//!     #[serde(with = "rust_money::serde::as_string")]
+//!     fee: Money,
+//! }
+//!
+//! let charge = Charge { price: Money::new(1234, "USD"), fee: Money::new(30, "USD") };
+//! let json = serde_json::to_string(&charge).unwrap();
+//! assert_eq!(json, r#"{"price":{"amount":1234,"currency":"usd"},"fee":"0.30 USD"}"#);
+//! ```
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use ::serde::de::Error as _;
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Currencies, Currency, Formatter, Money, MoneyError, Rational, RoundingMode};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Money")]
+struct MoneyRepr<'a> {
+    amount: i64,
+    #[serde(borrow)]
+    currency: Cow<'a, str>,
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MoneyRepr {
+            amount: self.amount,
+            currency: Cow::Borrowed(self.currency.as_str()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MoneyRepr::deserialize(deserializer)?;
+        Ok(Money {
+            amount: repr.amount,
+            currency: lookup(&repr.currency)?.key(),
+        })
+    }
+}
+
+fn lookup<E: ::serde::de::Error>(code: &str) -> Result<Arc<Currency>, E> {
+    Currencies::lookup(code)
+        .ok_or_else(|| E::custom(format_args!("unknown currency code `{}`", code)))
+}
+
+// 以主单位表示的十进制字符串，不带符号和分组，例如 "-12.34"
+fn to_major(money: &Money) -> String {
+    Formatter::new(money.currency.fraction() as usize, ".", "", "", "1").format(money.amount)
+}
+
+fn from_major<E: ::serde::de::Error>(amount: &str, currency: &Currency) -> Result<Money, E> {
+    let invalid = |err: MoneyError| E::custom(format_args!("invalid amount `{}`: {}", amount, err));
+    if amount.contains(['/', '%']) {
+        return Err(invalid(MoneyError::InvalidRational));
+    }
+    let value: Rational = amount.parse().map_err(invalid)?;
+    let factor = crate::rounding::pow10(currency.fraction as u32).map_err(invalid)?;
+    let minor = crate::rounding::mul_div_round(value.numer(), factor, value.denom(), RoundingMode::Unnecessary)
+        .and_then(crate::rounding::to_i64)
+        .map_err(invalid)?;
+    Ok(Money {
+        amount: minor,
+        currency: currency.key(),
+    })
+}
+
+/// The default representation, `{"amount": 1234, "currency": "USD"}`, with
+/// the amount in minor units.
+pub mod minor_units {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(money: &Money, serializer: S) -> Result<S::Ok, S::Error> {
+        money.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Money, D::Error> {
+        Money::deserialize(deserializer)
+    }
+}
+
+/// `{"amount": "12.34", "currency": "USD"}`, with the amount as a decimal
+/// string in major units. Amounts with more digits than the currency's
+/// fraction are rejected rather than rounded.
+pub mod major_units {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Money")]
+    struct Repr<'a> {
+        #[serde(borrow)]
+        amount: Cow<'a, str>,
+        #[serde(borrow)]
+        currency: Cow<'a, str>,
+    }
+
+    pub fn serialize<S: Serializer>(money: &Money, serializer: S) -> Result<S::Ok, S::Error> {
+        Repr {
+            amount: Cow::Owned(to_major(money)),
+            currency: Cow::Borrowed(money.currency.as_str()),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Money, D::Error> {
+        let repr = Repr::deserialize(deserializer)?;
+        let currency = lookup(&repr.currency)?;
+        from_major(&repr.amount, &currency)
+    }
+}
+
+/// `{"amount": 1234, "currency": "usd"}`, the minor-unit form with a
+/// lower-case currency code as used by Stripe. Any case is accepted when
+/// deserializing.
+pub mod lowercase {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(money: &Money, serializer: S) -> Result<S::Ok, S::Error> {
+        MoneyRepr {
+            amount: money.amount,
+            currency: Cow::Owned(money.currency.as_str().to_lowercase()),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Money, D::Error> {
+        Money::deserialize(deserializer)
+    }
+}
+
+/// `"12.34 USD"`: the amount in major units and the currency code in a
+/// single string.
+pub mod as_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(money: &Money, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{} {}", to_major(money), money.currency.as_str()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Money, D::Error> {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        let (amount, code) = s
+            .trim()
+            .rsplit_once(' ')
+            .ok_or_else(|| D::Error::custom(format_args!("expected \"<amount> <currency>\", got `{}`", s)))?;
+        let currency = lookup(code)?;
+        from_major(amount.trim_end(), &currency)
+    }
+}
+
+/// A bare minor-unit amount, `1234`, for payloads that carry the currency
+/// elsewhere.
+///
+/// The currency is not part of the value, so this module cannot be used
+/// with `#[serde(with)]`. Serialize with [`serialize`](amount_only::serialize)
+/// and, when the currency is fixed, deserialize with
+/// [`deserialize_in`](amount_only::deserialize_in) and the tag type of the
+/// currency:
+///
+/// ```
+/// use rust_money::serde::amount_only;
+/// use rust_money::Money;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Price {
+///     #[serde(serialize_with = "amount_only::serialize")]
+///     #[serde(deserialize_with = "amount_only::deserialize_in::<rust_money::typed::EUR, _>")]
+///     net: Money,
+/// }
+///
+/// let json = serde_json::to_string(&Price { net: Money::new(999, "EUR") }).unwrap();
+/// assert_eq!(json, r#"{"net":999}"#);
+/// assert_eq!(serde_json::from_str::<Price>(&json).unwrap().net, Money::new(999, "EUR"));
+/// ```
+///
+/// When the code is only known at runtime, e.g. from a sibling field, use
+/// the [`AmountOnly`](amount_only::AmountOnly) seed:
+///
+/// ```
+/// use rust_money::serde::amount_only::AmountOnly;
+/// use rust_money::Money;
+/// use serde::de::DeserializeSeed;
+///
+/// let mut de = serde_json::Deserializer::from_str("1234");
+/// assert_eq!(AmountOnly("usd").deserialize(&mut de).unwrap(), Money::new(1234, "USD"));
+/// let mut de = serde_json::Deserializer::from_str("1");
+/// assert!(AmountOnly("XYZ").deserialize(&mut de).is_err());
+/// ```
+pub mod amount_only {
+    use ::serde::de::DeserializeSeed;
+
+    use super::*;
+    use crate::currency::Code;
+    use crate::typed::CurrencyTag;
+
+    pub fn serialize<S: Serializer>(money: &Money, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(money.amount)
+    }
+
+    /// Reads a bare minor-unit amount in the built-in currency `C`, for
+    /// `#[serde(deserialize_with = "amount_only::deserialize_in::<EUR, _>")]`.
+    pub fn deserialize_in<'de, C: CurrencyTag, D: Deserializer<'de>>(deserializer: D) -> Result<Money, D::Error> {
+        Ok(Money {
+            amount: i64::deserialize(deserializer)?,
+            currency: Code::from(C::CODE),
+        })
+    }
+
+    /// Reads a bare minor-unit amount in the currency with the given code,
+    /// rejecting unknown codes like the other representations.
+    #[derive(Debug, Clone, Copy)]
+    pub struct AmountOnly<'a>(pub &'a str);
+
+    impl<'de> DeserializeSeed<'de> for AmountOnly<'_> {
+        type Value = Money;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Money, D::Error> {
+            let amount = i64::deserialize(deserializer)?;
+            Ok(Money {
+                amount,
+                currency: lookup(self.0)?.key(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for m in [Money::new(-5, "JPY"), Money::new(i64::MAX, "BHD"), Money::new(0, "eur")] {
+            let json = serde_json::to_string(&m).unwrap();
+            let back: Money = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, m);
+            assert_eq!(back.currency().fraction(), m.currency().fraction());
+        }
+    }
+
+    #[test]
+    fn test_deserialize_rebuilds_currency() {
+        let m: Money = serde_json::from_str(r#"{"currency":"gbp","amount":100}"#).unwrap();
+        assert_eq!(m.to_string(), "GBP £1.00");
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Prices {
+        #[serde(with = "minor_units")]
+        minor: Money,
+        #[serde(with = "major_units")]
+        major: Money,
+        #[serde(with = "lowercase")]
+        lower: Money,
+        #[serde(with = "as_string")]
+        string: Money,
+    }
+
+    #[test]
+    fn test_helper_round_trips() {
+        for (amount, code) in [(1234, "USD"), (-5, "USD"), (-1234567, "JPY"), (1, "BHD"), (0, "EUR")] {
+            let m = Money::new(amount, code);
+            let prices = Prices { minor: m, major: m, lower: m, string: m };
+            let json = serde_json::to_string(&prices).unwrap();
+            assert_eq!(serde_json::from_str::<Prices>(&json).unwrap(), prices, "{}", json);
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(try_from = "RawLine")]
+    struct Line {
+        currency: String,
+        #[serde(serialize_with = "amount_only::serialize")]
+        price: Money,
+    }
+
+    #[derive(Deserialize)]
+    struct RawLine {
+        currency: String,
+        price: i64,
+    }
+
+    impl TryFrom<RawLine> for Line {
+        type Error = ::serde::de::value::Error;
+
+        fn try_from(raw: RawLine) -> Result<Self, Self::Error> {
+            use ::serde::de::{DeserializeSeed, IntoDeserializer};
+            let price = amount_only::AmountOnly(&raw.currency).deserialize(raw.price.into_deserializer())?;
+            Ok(Line { currency: raw.currency, price })
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fixed {
+        #[serde(serialize_with = "amount_only::serialize", deserialize_with = "amount_only::deserialize_in::<crate::typed::BHD, _>")]
+        price: Money,
+    }
+
+    #[test]
+    fn test_amount_only_in_fixed_currency() {
+        for amount in [0, -1, i64::MIN, i64::MAX] {
+            let fixed = Fixed { price: Money::new(amount, "BHD") };
+            let json = serde_json::to_string(&fixed).unwrap();
+            assert_eq!(json, format!(r#"{{"price":{}}}"#, amount));
+            assert_eq!(serde_json::from_str::<Fixed>(&json).unwrap(), fixed);
+        }
+        assert!(serde_json::from_str::<Fixed>(r#"{"price":1.5}"#).is_err());
+        // 不依赖注册表
+        let _scope = Currencies::scoped(Currencies::new());
+        assert_eq!(serde_json::from_str::<Fixed>(r#"{"price":1}"#).unwrap().price.currency().fraction(), 3);
+    }
+
+    #[test]
+    fn test_amount_only_round_trip() {
+        for (amount, code) in [(999, "EUR"), (-5, "JPY"), (i64::MIN, "BHD"), (0, "usd")] {
+            let line = Line { currency: code.to_string(), price: Money::new(amount, code) };
+            let json = serde_json::to_string(&line).unwrap();
+            assert_eq!(json, format!(r#"{{"currency":"{}","price":{}}}"#, code, amount));
+            assert_eq!(serde_json::from_str::<Line>(&json).unwrap(), line);
+        }
+        let err = serde_json::from_str::<Line>(r#"{"currency":"XYZ","price":1}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown currency code `XYZ`"));
+        assert!(serde_json::from_str::<Line>(r#"{"currency":"USD","price":"1"}"#).is_err());
+    }
+
+    #[test]
+    fn test_helper_formats() {
+        let m = Money::new(-1205, "USD");
+        let prices = Prices { minor: m, major: m, lower: m, string: m };
+        assert_eq!(
+            serde_json::to_string(&prices).unwrap(),
+            r#"{"minor":{"amount":-1205,"currency":"USD"},"major":{"amount":"-12.05","currency":"USD"},"lower":{"amount":-1205,"currency":"usd"},"string":"-12.05 USD"}"#
+        );
+    }
+
+    #[test]
+    fn test_helper_errors() {
+        let de = |json: &str| major_units::deserialize(&mut serde_json::Deserializer::from_str(json));
+        assert_eq!(de(r#"{"amount":"12.3","currency":"USD"}"#).unwrap(), Money::new(1230, "USD"));
+        assert!(de(r#"{"amount":"12.345","currency":"USD"}"#).is_err());
+        assert!(de(r#"{"amount":"1/3","currency":"USD"}"#).is_err());
+        assert!(de(r#"{"amount":12.34,"currency":"USD"}"#).is_err());
+        let de = |json: &str| as_string::deserialize(&mut serde_json::Deserializer::from_str(json));
+        assert_eq!(de(r#""12 jpy""#).unwrap(), Money::new(12, "JPY"));
+        assert!(de(r#""12.00""#).is_err());
+        assert!(de(r#""12.00 XYZ""#).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_errors() {
+        let err = serde_json::from_str::<Money>(r#"{"amount":1,"currency":"XYZ"}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown currency code `XYZ`"));
+        assert!(serde_json::from_str::<Money>(r#"{"amount":1}"#).is_err());
+        assert!(serde_json::from_str::<Money>(r#"{"amount":1.5,"currency":"USD"}"#).is_err());
+    }
+}
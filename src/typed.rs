@@ -0,0 +1,243 @@
+//! Money whose currency is part of its type.
+//!
+//! [`TypedMoney<C>`] carries its currency as a zero-sized tag type, one for
+//! each code of the built-in table, so mixing currencies is a compile
+//! error rather than a runtime `MoneyError::CurrencyMismatch`:
+//!
+//! ```
+//! use rust_money::typed::{TypedMoney, EUR, USD};
+//!
+//! let price = TypedMoney::<EUR>::new(1999);
+//! let shipping = TypedMoney::<EUR>::new(500);
+//! assert_eq!((price + shipping).amount(), 2499);
+//!
+//! // let wrong = price + TypedMoney::<USD>::new(1); // does not compile
+//! let dollars = TypedMoney::<USD>::new(1);
+//! assert_eq!(dollars.to_money().to_string(), "USD $0.01");
+//! ```
+//!
+//! Conversions to and from the dynamic [`Money`] are provided through
+//! `From` and `TryFrom`.
+
+use std::fmt;
+use std::hash::Hash;
+use std::iter::Sum;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::Arc;
+
+use crate::currency::Code;
+use crate::{Currency, CurrencyCode, Money, MoneyError};
+
+pub use crate::constants::tags::*;
+
+/// A type-level currency. Implemented by the tag types of this module, one
+/// per built-in currency.
+pub trait CurrencyTag: fmt::Debug + Copy + Eq + Ord + Hash + Default + 'static {
+    const CODE: CurrencyCode;
+}
+
+/// An amount in the minor units of the currency `C`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct TypedMoney<C: CurrencyTag> {
+    amount: i64,
+    currency: PhantomData<C>,
+}
+
+impl<C: CurrencyTag> TypedMoney<C> {
+    pub const fn new(amount: i64) -> Self {
+        TypedMoney {
+            amount,
+            currency: PhantomData,
+        }
+    }
+
+    pub const fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub const fn code(&self) -> CurrencyCode {
+        C::CODE
+    }
+
+    /// The definition of the currency; see [`Money::currency`].
+    pub fn currency(&self) -> Arc<Currency> {
+        Code::from(C::CODE).currency()
+    }
+
+    /// Converts to a [`Money`] in the built-in definition of `C`, without
+    /// consulting the registry.
+    pub fn to_money(self) -> Money {
+        Money {
+            amount: self.amount,
+            currency: Code::from(C::CODE),
+        }
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, MoneyError> {
+        self.amount.checked_add(other.amount).map(Self::new).ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, MoneyError> {
+        self.amount.checked_sub(other.amount).map(Self::new).ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_mul(self, multiplier: i64) -> Result<Self, MoneyError> {
+        self.amount.checked_mul(multiplier).map(Self::new).ok_or(MoneyError::Overflow)
+    }
+}
+
+impl<C: CurrencyTag> fmt::Display for TypedMoney<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_money().fmt(f)
+    }
+}
+
+impl<C: CurrencyTag> From<TypedMoney<C>> for Money {
+    fn from(money: TypedMoney<C>) -> Self {
+        money.to_money()
+    }
+}
+
+/// Fails with `MoneyError::CurrencyMismatch` unless the money is in `C`.
+///
+/// ```
+/// use rust_money::typed::{TypedMoney, GBP};
+/// use rust_money::{Money, MoneyError};
+///
+/// assert_eq!(TypedMoney::<GBP>::try_from(Money::new(5, "GBP")), Ok(TypedMoney::new(5)));
+/// assert_eq!(TypedMoney::<GBP>::try_from(Money::new(5, "EUR")), Err(MoneyError::CurrencyMismatch));
+/// ```
+impl<C: CurrencyTag> TryFrom<Money> for TypedMoney<C> {
+    type Error = MoneyError;
+
+    fn try_from(money: Money) -> Result<Self, Self::Error> {
+        TypedMoney::try_from(&money)
+    }
+}
+
+impl<C: CurrencyTag> TryFrom<&Money> for TypedMoney<C> {
+    type Error = MoneyError;
+
+    fn try_from(money: &Money) -> Result<Self, Self::Error> {
+        if money.currency != Code::from(C::CODE) {
+            return Err(MoneyError::CurrencyMismatch);
+        }
+        Ok(TypedMoney::new(money.amount()))
+    }
+}
+
+// 同一货币之间的运算不会出错，溢出时与整数运算一样 panic
+impl<C: CurrencyTag> Add for TypedMoney<C> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).expect("attempt to add with overflow")
+    }
+}
+
+impl<C: CurrencyTag> Sub for TypedMoney<C> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).expect("attempt to subtract with overflow")
+    }
+}
+
+impl<C: CurrencyTag> Neg for TypedMoney<C> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(self.amount.checked_neg().expect("attempt to negate with overflow"))
+    }
+}
+
+impl<C: CurrencyTag> Mul<i64> for TypedMoney<C> {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self {
+        self.checked_mul(rhs).expect("attempt to multiply with overflow")
+    }
+}
+
+impl<C: CurrencyTag> AddAssign for TypedMoney<C> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<C: CurrencyTag> SubAssign for TypedMoney<C> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<C: CurrencyTag> MulAssign<i64> for TypedMoney<C> {
+    fn mul_assign(&mut self, rhs: i64) {
+        *self = *self * rhs;
+    }
+}
+
+impl<C: CurrencyTag> Sum for TypedMoney<C> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<'a, C: CurrencyTag> Sum<&'a TypedMoney<C>> for TypedMoney<C> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let mut m = TypedMoney::<JPY>::new(100);
+        m += TypedMoney::new(50);
+        m -= TypedMoney::new(30);
+        m *= 2;
+        assert_eq!(m, TypedMoney::new(240));
+        assert_eq!(-m, TypedMoney::new(-240));
+        assert!(m > TypedMoney::new(239));
+        assert_eq!(TypedMoney::<JPY>::new(i64::MAX).checked_add(TypedMoney::new(1)), Err(MoneyError::Overflow));
+        let total: TypedMoney<JPY> = [m, m, m].iter().sum();
+        assert_eq!(total.amount(), 720);
+        assert_eq!(Vec::<TypedMoney<JPY>>::new().into_iter().sum::<TypedMoney<JPY>>().amount(), 0);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let typed = TypedMoney::<BHD>::new(1234);
+        let money: Money = typed.into();
+        assert_eq!(money, Money::new(1234, "BHD"));
+        assert_eq!(money.currency().fraction(), 3);
+        assert_eq!(TypedMoney::<BHD>::try_from(&money), Ok(typed));
+        assert_eq!(TypedMoney::<USD>::try_from(&money), Err(MoneyError::CurrencyMismatch));
+        assert_eq!(typed.code(), CurrencyCode::BHD);
+        assert_eq!(typed.to_string(), money.to_string());
+        assert_eq!(std::mem::size_of::<TypedMoney<BHD>>(), 8);
+    }
+
+    #[test]
+    fn test_conversions_without_registry() {
+        let _scope = crate::Currencies::scoped(crate::Currencies::new());
+        let money = TypedMoney::<BHD>::new(1234).to_money();
+        assert_eq!(money.to_string(), "BHD 1.234 .د.ب");
+        assert_eq!(TypedMoney::<BHD>::try_from(money), Ok(TypedMoney::new(1234)));
+    }
+
+    #[test]
+    fn test_conversions_with_redefined_currency() {
+        let _scope = crate::Currencies::scoped(crate::Currencies::default());
+        crate::Currencies::register(Currency::new_all("USD", "$", "840", "$1", ".", ",", 3));
+        // 小数位数不同的 Money 不是内置定义的 USD
+        assert_eq!(TypedMoney::<USD>::try_from(Money::new(1000, "USD")), Err(MoneyError::CurrencyMismatch));
+        let typed = TypedMoney::<USD>::new(1000);
+        assert_eq!(typed.currency().fraction(), 2);
+        assert_eq!(typed.to_string(), "USD $10.00");
+    }
+}